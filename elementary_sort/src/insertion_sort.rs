@@ -1,11 +1,58 @@
-fn insertion_sort<T: PartialOrd>(data: &mut [T]){
+//!
+//! Insertion sort: move each entry to the left, exchanging it with the larger ones before it.
+//! Linear on partially sorted input, about N^2 / 4 compares on random input. Stable.
+//!
+//! ```rust
+//!     # use elementary_sort::insertion_sort;
+//!     let mut data = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+//!     insertion_sort::sort_by_key(&mut data, |&(k, _)| k);
+//!     // equal keys keep their order
+//!     assert_eq!([(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')], data);
+//! ```
 
-    for i in 0..data.len(){
+use std::cmp::Ordering;
+
+use crate::trace::Event;
+
+pub fn insertion_sort<T: PartialOrd>(data: &mut [T]){
+    sort_by_less(data, |a, b| a < b);
+}
+
+pub fn sort<T: Ord>(data: &mut [T]){
+    sort_by_less(data, |a, b| a < b);
+}
+
+/// Sort with a comparator, as slice::sort_by
+pub fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(data: &mut [T], mut compare: F){
+    sort_by_less(data, |a, b| compare(a, b) == Ordering::Less);
+}
+
+/// Sort by the key extracted from each entry, as slice::sort_by_key
+pub fn sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(data: &mut [T], mut key: F){
+    sort_by_less(data, |a, b| key(a) < key(b));
+}
+
+/// Sort reporting every compare and exchange to the observer. A pass ends when an entry has been inserted
+pub fn sort_traced<T: Ord, O: FnMut(Event)>(data: &mut [T], observer: O){
+    sort_observed(data, |a, b| a < b, observer);
+}
+
+fn sort_by_less<T, F: FnMut(&T, &T) -> bool>(data: &mut [T], less: F){
+    sort_observed(data, less, |_| {});
+}
+
+fn sort_observed<T, F: FnMut(&T, &T) -> bool, O: FnMut(Event)>(data: &mut [T], mut less: F, mut observe: O){
+
+    for i in 1..data.len(){
         let mut j = i;
-        while j > 0 && data[j] < data[j-1] {
+        while j > 0 {
+            observe(Event::Compare(j, j-1));
+            if !less(&data[j], &data[j-1]) {break}
             data.swap(j, j-1);
-            j = j - 1;
+            observe(Event::Exchange(j, j-1));
+            j -= 1;
         }
+        observe(Event::Pass);
     }
 }
 
@@ -33,4 +80,41 @@ mod tests {
         insertion_sort(&mut data);
         assert_eq!(&["a", "b", "c", "f"], &data);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn empty_and_single() {
+        let mut empty: [i32; 0] = [];
+        sort(&mut empty);
+
+        let mut single = [7];
+        sort(&mut single);
+        assert_eq!([7], single);
+    }
+
+    #[test]
+    fn comparators() {
+        let mut data = [3, 1, 4, 1, 5, 9, 2, 6];
+        sort(&mut data);
+        assert_eq!([1, 1, 2, 3, 4, 5, 6, 9], data);
+
+        sort_by(&mut data, |a, b| b.cmp(a));
+        assert_eq!([9, 6, 5, 4, 3, 2, 1, 1], data);
+
+        let mut words = ["ccc", "a", "bb", "d"];
+        sort_by_key(&mut words, |w| w.len());
+        assert_eq!(["a", "d", "bb", "ccc"], words);
+    }
+
+    #[test]
+    fn traced() {
+        let mut data = [3, 1, 2];
+        let mut events = Vec::new();
+        sort_traced(&mut data, |e| events.push(e));
+
+        assert_eq!([1, 2, 3], data);
+        assert_eq!(vec![
+            Event::Compare(1, 0), Event::Exchange(1, 0), Event::Pass,
+            Event::Compare(2, 1), Event::Exchange(2, 1), Event::Compare(1, 0), Event::Pass
+        ], events);
+    }
+}
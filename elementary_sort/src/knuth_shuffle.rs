@@ -1,10 +1,158 @@
+//!
+//! Knuth shuffle: at the i-th step exchange the i-th entry with a random one among the first i+1.
+//! Every permutation comes out with the same probability, in linear time.
+//!
+//! The `_with` variants, and the sampling functions, draw from any generator, so a seeded one reproduces the result.
+//!
+//! ```rust
+//!     # use elementary_sort::knuth_shuffle::{knuth_shuffling_with, permutation};
+//!     # use rand::SeedableRng;
+//!     # use rand::rngs::StdRng;
+//!     let mut a = [1, 2, 3, 4, 5];
+//!     let mut b = a;
+//!     knuth_shuffling_with(&mut a, &mut StdRng::seed_from_u64(7));
+//!     knuth_shuffling_with(&mut b, &mut StdRng::seed_from_u64(7));
+//!     assert_eq!(a, b);
+//!
+//!     let mut p = permutation(5, &mut StdRng::seed_from_u64(7));
+//!     p.sort();
+//!     assert_eq!(vec![0, 1, 2, 3, 4], p);
+//! ```
+
 use rand::Rng;
 
 pub fn knuth_shuffling<T>(data: &mut [T]){
+    knuth_shuffling_with(data, &mut rand::thread_rng());
+}
+
+/// Same as knuth_shuffling, drawing from the given generator so the shuffle can be reproduced
+pub fn knuth_shuffling_with<T, R: Rng + ?Sized>(data: &mut [T], rng: &mut R){
 
     for i in 0..data.len(){
-        let rand_i = rand::thread_rng().gen_range(0..i+1);
+        let rand_i = rng.gen_range(0..i+1);
         data.swap(i, rand_i);
     }
 
 }
+
+/// Shuffle only the first k positions: they get k entries chosen uniformly at random, in random order.
+/// The rest of the slice holds the entries left out, in no particular order. Takes O(k)
+pub fn partial_shuffling<T>(data: &mut [T], k: usize){
+    partial_shuffling_with(data, k, &mut rand::thread_rng());
+}
+
+/// Same as partial_shuffling, drawing from the given generator
+pub fn partial_shuffling_with<T, R: Rng + ?Sized>(data: &mut [T], k: usize, rng: &mut R){
+
+    // the i-th position takes a random entry among the ones not chosen yet
+    for i in 0..k.min(data.len()){
+        let rand_i = rng.gen_range(i..data.len());
+        data.swap(i, rand_i);
+    }
+
+}
+
+/// Choose k items uniformly at random from the iterator, reading it once without knowing its length.
+/// Returns every item when there are less than k. The order of the sample is not random
+pub fn reservoir_sample<T, I: IntoIterator<Item = T>, R: Rng + ?Sized>(items: I, k: usize, rng: &mut R) -> Vec<T> {
+    let items = items.into_iter();
+    // k may be far larger than the input, do not trust it for the allocation
+    let mut sample = Vec::with_capacity(k.min(items.size_hint().0));
+    if k == 0 {return sample}
+
+    for (i, item) in items.enumerate(){
+        if i < k {
+            sample.push(item);
+        } else {
+            // the i-th item is kept with probability k / (i + 1)
+            let rand_i = rng.gen_range(0..i+1);
+            if rand_i < k {
+                sample[rand_i] = item;
+            }
+        }
+    }
+
+    sample
+}
+
+/// Return a random permutation of 0..n
+pub fn permutation<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Vec<usize> {
+    let mut p: Vec<usize> = (0..n).collect();
+    knuth_shuffling_with(&mut p, rng);
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn reproducible() {
+        let p = permutation(20, &mut StdRng::seed_from_u64(1));
+        assert_eq!(p, permutation(20, &mut StdRng::seed_from_u64(1)));
+        assert_ne!(p, permutation(20, &mut StdRng::seed_from_u64(2)));
+
+        let mut sorted = p.clone();
+        sorted.sort();
+        assert_eq!((0..20).collect::<Vec<_>>(), sorted);
+    }
+
+    #[test]
+    fn partial() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut data: Vec<u32> = (0..10).collect();
+        partial_shuffling_with(&mut data, 3, &mut rng);
+
+        let mut sorted = data.clone();
+        sorted.sort();
+        assert_eq!((0..10).collect::<Vec<_>>(), sorted);
+
+        // k larger than the slice shuffles all of it
+        let mut small = [1, 2, 3];
+        partial_shuffling_with(&mut small, 10, &mut rng);
+        small.sort();
+        assert_eq!([1, 2, 3], small);
+
+        let mut empty: [u8; 0] = [];
+        partial_shuffling(&mut empty, 2);
+    }
+
+    #[test]
+    fn reservoir() {
+        let mut rng = StdRng::seed_from_u64(5);
+        assert_eq!(vec![0, 1, 2], reservoir_sample(0..3, 5, &mut rng));
+        assert!(reservoir_sample(0..3, 0, &mut rng).is_empty());
+        // a huge k only costs the items actually read
+        assert_eq!(vec![0, 1, 2], reservoir_sample(0..3, usize::MAX / 8, &mut rng));
+        assert_eq!(vec![7], reservoir_sample(std::iter::once(7).filter(|_| true), usize::MAX, &mut rng));
+
+        let sample = reservoir_sample(0..1000, 10, &mut rng);
+        assert_eq!(10, sample.len());
+        let mut distinct = sample.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(10, distinct.len());
+    }
+
+    #[test]
+    fn uniform() {
+        // each of the 5 items ends up in a sample of 2 about 2/5 of the times
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut hits = [0; 5];
+        for _ in 0..10_000{
+            for i in reservoir_sample(0..5, 2, &mut rng){
+                hits[i] += 1;
+            }
+        }
+        assert!(hits.iter().all(|&h| h > 3700 && h < 4300), "{:?}", hits);
+
+        // and the first position of a shuffle takes each value about 1/5 of the times
+        let mut first = [0; 5];
+        for _ in 0..10_000{
+            first[permutation(5, &mut rng)[0]] += 1;
+        }
+        assert!(first.iter().all(|&h| h > 1800 && h < 2200), "{:?}", first);
+    }
+}
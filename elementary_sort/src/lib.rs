@@ -1,4 +1,5 @@
-mod selection_sort;
-mod insertion_sort;
-mod shellsort;
-pub mod knuth_shuffle; 
\ No newline at end of file
+pub mod selection_sort;
+pub mod insertion_sort;
+pub mod shellsort;
+pub mod knuth_shuffle; 
+pub mod trace;
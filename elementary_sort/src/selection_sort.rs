@@ -1,15 +1,66 @@
-fn selection_sort<T: PartialOrd>(data: &mut [T]) -> &[T] {
-    
-    for i in 0..data.len()-1{
-        let min = min_index(&data[i..]);
-        data.swap(i, i+min);
-    }
+//!
+//! Selection sort: at the i-th step find the minimum of the remaining entries and exchange it with the i-th one.
+//! Uses about N^2 / 2 compares and N exchanges whatever the input. Not stable.
+//!
+//! ```rust
+//!     # use elementary_sort::selection_sort;
+//!     let mut data = ["pear", "fig", "apple"];
+//!     selection_sort::sort_by_key(&mut data, |s| s.len());
+//!     assert_eq!(["fig", "pear", "apple"], data);
+//! ```
+
+use std::cmp::Ordering;
+
+use crate::trace::Event;
+
+pub fn selection_sort<T: PartialOrd>(data: &mut [T]) -> &[T] {
+    sort_by_less(data, |a, b| a < b);
     data
 }
 
-fn min_index<T: PartialOrd>(data: &[T]) -> usize {
+pub fn sort<T: Ord>(data: &mut [T]){
+    sort_by_less(data, |a, b| a < b);
+}
+
+/// Sort with a comparator, as slice::sort_by
+pub fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(data: &mut [T], mut compare: F){
+    sort_by_less(data, |a, b| compare(a, b) == Ordering::Less);
+}
+
+/// Sort by the key extracted from each entry, as slice::sort_by_key
+pub fn sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(data: &mut [T], mut key: F){
+    sort_by_less(data, |a, b| key(a) < key(b));
+}
+
+/// Sort reporting every compare and exchange to the observer. A pass ends when an entry reaches its final place
+pub fn sort_traced<T: Ord, O: FnMut(Event)>(data: &mut [T], observer: O){
+    sort_observed(data, |a, b| a < b, observer);
+}
+
+fn sort_by_less<T, F: FnMut(&T, &T) -> bool>(data: &mut [T], less: F){
+    sort_observed(data, less, |_| {});
+}
+
+fn sort_observed<T, F: FnMut(&T, &T) -> bool, O: FnMut(Event)>(data: &mut [T], mut less: F, mut observe: O){
+    // the last entry is in place once the others are
+    for i in 0..data.len().saturating_sub(1){
+        let mut min = i;
+        for j in i+1..data.len(){
+            observe(Event::Compare(j, min));
+            if less(&data[j], &data[min]) {
+                min = j;
+            }
+        }
+        data.swap(i, min);
+        observe(Event::Exchange(i, min));
+        observe(Event::Pass);
+    }
+}
+
+/// Return the index of the first minimum, 0 for an empty slice
+pub fn min_index<T: PartialOrd>(data: &[T]) -> usize {
     let mut min: usize = 0;
-    for i in 0..data.len() {
+    for i in 1..data.len() {
         if data[i] < data[min] {
             min = i;
         }
@@ -30,15 +81,15 @@ mod tests {
 
     #[test]
     fn test_min() {
-        let mut data = [5, 2, 4, 9, 1, 6];
-        let min_i = min_index(&mut data);
+        let data = [5, 2, 4, 9, 1, 6];
+        let min_i = min_index(&data);
         assert_eq!(min_i, 4);
     }
 
     #[test]
     fn test_min2() {
-        let mut data = [4, 5, 2, 1, 1, 6, 9, 4, 2, 0];
-        let min_i = min_index(&mut data);
+        let data = [4, 5, 2, 1, 1, 6, 9, 4, 2, 0];
+        let min_i = min_index(&data);
         assert_eq!(min_i, 9);
     }
 
@@ -55,4 +106,43 @@ mod tests {
         selection_sort(&mut data);
         assert_eq!(&["a", "b", "c", "f"], &data);
     }
+
+    #[test]
+    fn empty_and_single() {
+        let mut empty: [i32; 0] = [];
+        selection_sort(&mut empty);
+        sort(&mut empty);
+
+        let mut single = [7];
+        sort(&mut single);
+        assert_eq!([7], single);
+        assert_eq!(0, min_index::<i32>(&[]));
+    }
+
+    #[test]
+    fn comparators() {
+        let mut data = [3, 1, 4, 1, 5, 9, 2, 6];
+        sort(&mut data);
+        assert_eq!([1, 1, 2, 3, 4, 5, 6, 9], data);
+
+        sort_by(&mut data, |a, b| b.cmp(a));
+        assert_eq!([9, 6, 5, 4, 3, 2, 1, 1], data);
+
+        let mut words = ["ccc", "a", "bb"];
+        sort_by_key(&mut words, |w| w.len());
+        assert_eq!(["a", "bb", "ccc"], words);
+    }
+
+    #[test]
+    fn traced() {
+        let mut data = [3, 1, 2];
+        let mut events = Vec::new();
+        sort_traced(&mut data, |e| events.push(e));
+
+        assert_eq!([1, 2, 3], data);
+        assert_eq!(vec![
+            Event::Compare(1, 0), Event::Compare(2, 1), Event::Exchange(0, 1), Event::Pass,
+            Event::Compare(2, 1), Event::Exchange(1, 2), Event::Pass
+        ], events);
+    }
 }
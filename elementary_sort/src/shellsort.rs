@@ -1,26 +1,174 @@
-fn shell_sort<T: PartialOrd>(data: &mut [T]){
+//!
+//! Shellsort: h-sort the slice, insertion sort moving entries h positions at a time, for a decreasing
+//! sequence of increments ending with 1. With the 3h+1 increments it uses O(N^3/2) compares. Not stable.
+//!
+//! ```rust
+//!     # use elementary_sort::shellsort;
+//!     let mut data = [5, 1, 4, 2, 3];
+//!     shellsort::sort_by(&mut data, |a, b| b.cmp(a));
+//!     assert_eq!([5, 4, 3, 2, 1], data);
+//! ```
+//!
+//! Other increment sequences can be chosen with `Gaps`, to see which one compares less on a given input.
+//!
+//! ```rust
+//!     # use elementary_sort::shellsort::{self, Gaps};
+//!     assert_eq!(vec![1, 4, 10, 23, 57], Gaps::Ciura.sequence(100));
+//!
+//!     let mut data = [5, 1, 4, 2, 3];
+//!     shellsort::sort_with_gaps(&mut data, &Gaps::Pratt);
+//!     assert_eq!([1, 2, 3, 4, 5], data);
+//! ```
 
-    let mut h = 1;
+use std::cmp::Ordering;
 
-    // compute progressive increment
-    while h < data.len() / 3 {
-        h = 3 * h + 1; // 1, 4, 13, 40, ...
+use crate::trace::Event;
+
+/// Increment sequences
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Gaps {
+    /// 1, 4, 13, 40, ... (3h+1), up to about N/3
+    #[default]
+    Knuth,
+    /// 1, 8, 23, 77, 281, ... (4^k + 3 * 2^(k-1) + 1)
+    Sedgewick,
+    /// 1, 4, 9, 20, 46, 103, ... (ceil of h = 2.25h + 1)
+    Tokuda,
+    /// 1, 4, 10, 23, 57, 132, 301, 701, 1750, then about 2.25 times the previous one
+    Ciura,
+    /// 1, 2, 3, 4, 6, 8, 9, 12, ... (2^p * 3^q), many passes but each one cheap
+    Pratt,
+    /// Any increments, in any order. 1 is added when missing, so the result is always sorted
+    Custom(Vec<usize>)
+}
+
+impl Gaps {
+
+    /// Return the increments used on n entries, in increasing order
+    pub fn sequence(&self, n: usize) -> Vec<usize> {
+        let mut gaps = vec![1];
+
+        match self {
+            Gaps::Knuth => {
+                let mut h = 1;
+                while h < n / 3 {
+                    h = 3 * h + 1;
+                    gaps.push(h);
+                }
+            },
+            Gaps::Sedgewick => {
+                for k in 1.. {
+                    let h = 4usize.pow(k) + 3 * 2usize.pow(k - 1) + 1;
+                    if h >= n {break}
+                    gaps.push(h);
+                }
+            },
+            Gaps::Tokuda => {
+                let mut h = 1.0f64;
+                loop {
+                    h = 2.25 * h + 1.0;
+                    let gap = h.ceil() as usize;
+                    if gap >= n {break}
+                    gaps.push(gap);
+                }
+            },
+            Gaps::Ciura => {
+                const CIURA: [usize; 8] = [4, 10, 23, 57, 132, 301, 701, 1750];
+                gaps.extend(CIURA.iter().copied().take_while(|&h| h < n));
+
+                let mut h = CIURA[CIURA.len() - 1];
+                loop {
+                    h = h * 9 / 4;
+                    if h >= n {break}
+                    gaps.push(h);
+                }
+            },
+            Gaps::Pratt => {
+                let mut pow2 = 1;
+                while pow2 < n {
+                    let mut h = pow2;
+                    while h < n {
+                        gaps.push(h);
+                        h *= 3;
+                    }
+                    pow2 *= 2;
+                }
+            },
+            Gaps::Custom(custom) => gaps.extend(custom.iter().copied().filter(|&h| h > 0 && h < n))
+        }
+
+        gaps.sort_unstable();
+        gaps.dedup();
+        gaps
     }
 
-    while h >= 1 {
+}
+
+pub fn shell_sort<T: PartialOrd>(data: &mut [T]){
+    sort_by_less(data, |a, b| a < b);
+}
+
+pub fn sort<T: Ord>(data: &mut [T]){
+    sort_by_less(data, |a, b| a < b);
+}
+
+/// Sort with a comparator, as slice::sort_by
+pub fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(data: &mut [T], mut compare: F){
+    sort_by_less(data, |a, b| compare(a, b) == Ordering::Less);
+}
+
+/// Sort by the key extracted from each entry, as slice::sort_by_key
+pub fn sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(data: &mut [T], mut key: F){
+    sort_by_less(data, |a, b| key(a) < key(b));
+}
+
+/// Sort with the given increment sequence
+pub fn sort_with_gaps<T: Ord>(data: &mut [T], gaps: &Gaps){
+    sort_by_less_with_gaps(data, gaps, |a, b| a < b);
+}
+
+/// Sort with a comparator and the given increment sequence
+pub fn sort_by_with_gaps<T, F: FnMut(&T, &T) -> Ordering>(data: &mut [T], gaps: &Gaps, mut compare: F){
+    sort_by_less_with_gaps(data, gaps, |a, b| compare(a, b) == Ordering::Less);
+}
+
+/// Sort reporting every compare and exchange to the observer. A pass ends when the slice is h-sorted
+pub fn sort_traced<T: Ord, O: FnMut(Event)>(data: &mut [T], observer: O){
+    sort_observed(data, &Gaps::Knuth, |a, b| a < b, observer);
+}
+
+/// Same as sort_traced, with the given increment sequence
+pub fn sort_traced_with_gaps<T: Ord, O: FnMut(Event)>(data: &mut [T], gaps: &Gaps, observer: O){
+    sort_observed(data, gaps, |a, b| a < b, observer);
+}
+
+fn sort_by_less<T, F: FnMut(&T, &T) -> bool>(data: &mut [T], less: F){
+    sort_by_less_with_gaps(data, &Gaps::Knuth, less);
+}
+
+fn sort_by_less_with_gaps<T, F: FnMut(&T, &T) -> bool>(data: &mut [T], gaps: &Gaps, less: F){
+    sort_observed(data, gaps, less, |_| {});
+}
+
+fn sort_observed<T, F: FnMut(&T, &T) -> bool, O: FnMut(Event)>(data: &mut [T], gaps: &Gaps, mut less: F, mut observe: O){
 
-        for i in 0..data.len(){
+    // largest increment first, ending with 1
+    for h in gaps.sequence(data.len()).into_iter().rev() {
+
+        for i in h..data.len(){
 
             let mut j = i;
-            while j >= h && data[j-h] > data[j]{
+            while j >= h {
+                observe(Event::Compare(j, j-h));
+                if !less(&data[j], &data[j-h]) {break}
                 data.swap(j, j-h);
+                observe(Event::Exchange(j, j-h));
                 j -= h;
             }  
         }
-        h /= 3;
+        observe(Event::Pass);
     }
 
-
 }
 
 #[cfg(test)]
@@ -47,4 +195,83 @@ mod tests {
         shell_sort(&mut data);
         assert_eq!(&["a", "b", "c", "f"], &data);
     }
+
+    #[test]
+    fn empty_and_single() {
+        let mut empty: [i32; 0] = [];
+        sort(&mut empty);
+
+        let mut single = [7];
+        sort(&mut single);
+        assert_eq!([7], single);
+    }
+
+    #[test]
+    fn comparators() {
+        let mut data: Vec<i32> = (0..100).map(|i| (i * 37) % 101).collect();
+        sort(&mut data);
+        assert!(data.windows(2).all(|w| w[0] <= w[1]));
+
+        sort_by(&mut data, |a, b| b.cmp(a));
+        assert!(data.windows(2).all(|w| w[0] >= w[1]));
+
+        let mut words = ["ccc", "a", "bb"];
+        sort_by_key(&mut words, |w| w.len());
+        assert_eq!(["a", "bb", "ccc"], words);
+    }
+
+    fn all_gaps() -> Vec<Gaps> {
+        vec![Gaps::Knuth, Gaps::Sedgewick, Gaps::Tokuda, Gaps::Ciura, Gaps::Pratt, Gaps::Custom(vec![7, 3]), Gaps::Custom(vec![])]
+    }
+
+    #[test]
+    fn sequences() {
+        assert_eq!(vec![1, 4, 13, 40, 121], Gaps::Knuth.sequence(200));
+        assert_eq!(vec![1, 8, 23, 77], Gaps::Sedgewick.sequence(200));
+        assert_eq!(vec![1, 4, 9, 20, 46, 103], Gaps::Tokuda.sequence(200));
+        assert_eq!(vec![1, 4, 10, 23, 57, 132], Gaps::Ciura.sequence(200));
+        assert_eq!(vec![1, 2, 3, 4, 6, 8, 9, 12, 16, 18], Gaps::Pratt.sequence(20));
+        assert_eq!(vec![1, 3, 7], Gaps::Custom(vec![7, 0, 3, 3]).sequence(20));
+
+        // Ciura's sequence goes on past its known terms
+        assert_eq!(3937, *Gaps::Ciura.sequence(5000).last().unwrap());
+        for gaps in all_gaps(){
+            assert_eq!(vec![1], gaps.sequence(0));
+        }
+    }
+
+    #[test]
+    fn every_sequence_sorts() {
+        use rand::{Rng, SeedableRng};
+        use rand::rngs::StdRng;
+
+        let mut rng = StdRng::seed_from_u64(23);
+        for &n in [0, 1, 2, 10, 100, 1000].iter(){
+            let data: Vec<u32> = (0..n).map(|_| rng.gen_range(0..50)).collect();
+            let mut expected = data.clone();
+            expected.sort();
+
+            for gaps in all_gaps(){
+                let mut sorted = data.clone();
+                sort_with_gaps(&mut sorted, &gaps);
+                assert_eq!(expected, sorted, "{:?} on {} entries", gaps, n);
+
+                sort_by_with_gaps(&mut sorted, &gaps, |a, b| b.cmp(a));
+                assert!(sorted.iter().rev().eq(expected.iter()), "{:?} on {} entries", gaps, n);
+            }
+        }
+    }
+
+    #[test]
+    fn traced() {
+        let mut data = [3, 1, 2];
+        let mut events = Vec::new();
+        sort_traced_with_gaps(&mut data, &Gaps::Custom(vec![2]), |e| events.push(e));
+
+        assert_eq!([1, 2, 3], data);
+        assert_eq!(vec![
+            Event::Compare(2, 0), Event::Exchange(2, 0), Event::Pass,
+            Event::Compare(1, 0), Event::Exchange(1, 0), Event::Compare(2, 1), Event::Pass
+        ], events);
+    }
 }
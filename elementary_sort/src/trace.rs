@@ -0,0 +1,220 @@
+//!
+//! Step by step view of the elementary sorts. Each sort has a `sort_traced` variant reporting the
+//! compares and exchanges it makes, and the end of each pass, to an observer.
+//! Replaying the events on the initial values gives the frames of an animation, rendered as ASCII bar charts or SVG.
+//!
+//! ```rust
+//!     # use elementary_sort::insertion_sort;
+//!     # use elementary_sort::trace::{self, Event};
+//!     let initial = [2, 1];
+//!     let mut data = initial;
+//!     let mut events = Vec::new();
+//!     insertion_sort::sort_traced(&mut data, |e| events.push(e));
+//!     assert_eq!(vec![Event::Compare(1, 0), Event::Exchange(1, 0), Event::Pass], events);
+//!
+//!     let frames = trace::ascii_frames(&initial, &events, 2);
+//!     // the initial frame, then one per event
+//!     assert_eq!(4, frames.len());
+//!     assert_eq!(" *\n**\nexchange 1 0\n", frames[2]);
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// What a sort did, on the indices of the slice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Compare(usize, usize),
+    Exchange(usize, usize),
+    /// End of a pass over the slice
+    Pass
+}
+
+/// The values after an event, along with the event itself. The first frame has no event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub values: Vec<usize>,
+    pub event: Option<Event>
+}
+
+impl Frame {
+
+    /// Check if the i-th bar is one of the entries of the event
+    fn involves(&self, i: usize) -> bool {
+        match self.event {
+            Some(Event::Compare(a, b)) | Some(Event::Exchange(a, b)) => i == a || i == b,
+            _ => false
+        }
+    }
+
+    fn caption(&self) -> Option<String> {
+        match self.event? {
+            Event::Compare(a, b) => Some(format!("compare {} {}", a, b)),
+            Event::Exchange(a, b) => Some(format!("exchange {} {}", a, b)),
+            Event::Pass => Some("end of pass".to_string())
+        }
+    }
+
+}
+
+/// Apply the events to the initial values, one frame per event after the initial one
+pub fn frames(initial: &[usize], events: &[Event]) -> Vec<Frame> {
+    let mut values = initial.to_vec();
+    let mut frames = Vec::with_capacity(events.len() + 1);
+    frames.push(Frame { values: values.clone(), event: None });
+
+    for &event in events {
+        if let Event::Exchange(a, b) = event {
+            values.swap(a, b);
+        }
+        frames.push(Frame { values: values.clone(), event: Some(event) });
+    }
+
+    frames
+}
+
+/// Draw the frame as `height` rows of bars, one column per entry, scaled to the largest value.
+/// Compared entries are drawn with `?`, exchanged ones with `*`, the others with `#`.
+/// The event, if any, is written on the last line
+pub fn render_ascii(frame: &Frame, height: usize) -> String {
+    let max = frame.values.iter().copied().max().unwrap_or(0).max(1);
+    let bar = |v: usize| (v * height).div_ceil(max);
+    let mut out = String::new();
+
+    for row in (1..=height).rev() {
+        for (i, &v) in frame.values.iter().enumerate() {
+            out.push(match (bar(v) >= row, frame.involves(i), frame.event) {
+                (false, _, _) => ' ',
+                (true, true, Some(Event::Exchange(..))) => '*',
+                (true, true, _) => '?',
+                (true, false, _) => '#'
+            });
+        }
+        // no trailing spaces, they would only make the frames harder to compare
+        let trimmed = out.trim_end_matches(' ').len();
+        out.truncate(trimmed);
+        out.push('\n');
+    }
+
+    if let Some(caption) = frame.caption() {
+        out.push_str(&caption);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Draw the frame as an SVG image, 100 pixels high with bars 10 pixels wide
+pub fn render_svg(frame: &Frame) -> String {
+    const HEIGHT: usize = 100;
+    const BAR: usize = 10;
+
+    let max = frame.values.iter().copied().max().unwrap_or(0).max(1);
+    let width = BAR * frame.values.len();
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = width, h = HEIGHT
+    );
+
+    for (i, &v) in frame.values.iter().enumerate() {
+        let h = v * HEIGHT / max;
+        let color = match (frame.involves(i), frame.event) {
+            (true, Some(Event::Exchange(..))) => "crimson",
+            (true, _) => "orange",
+            _ => "steelblue"
+        };
+        svg.push_str(&format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+            i * BAR, HEIGHT - h, BAR - 1, h, color
+        ));
+    }
+
+    if let Some(caption) = frame.caption() {
+        svg.push_str(&format!("  <title>{}</title>\n", caption));
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/// Render every frame of the events as ASCII
+pub fn ascii_frames(initial: &[usize], events: &[Event], height: usize) -> Vec<String> {
+    frames(initial, events).iter().map(|f| render_ascii(f, height)).collect()
+}
+
+/// Write every frame of the events as `frame_00000.svg`, `frame_00001.svg`, ... in the directory,
+/// creating it if needed. Return the number of files written
+pub fn write_svg_frames<P: AsRef<Path>>(dir: P, initial: &[usize], events: &[Event]) -> io::Result<usize> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    let frames = frames(initial, events);
+    for (i, frame) in frames.iter().enumerate() {
+        fs::write(dir.join(format!("frame_{:05}.svg", i)), render_svg(frame))?;
+    }
+
+    Ok(frames.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{insertion_sort, selection_sort, shellsort};
+
+    fn trace<S: FnOnce(&mut [usize], &mut dyn FnMut(Event))>(initial: &[usize], sort: S) -> Vec<Event> {
+        let mut data = initial.to_vec();
+        let mut events = Vec::new();
+        sort(&mut data, &mut |e| events.push(e));
+        events
+    }
+
+    #[test]
+    fn replay_sorts() {
+        let initial = [5, 3, 8, 1, 9, 2, 7, 4, 6, 0];
+        let mut sorted = initial.to_vec();
+        sorted.sort();
+
+        let traces = vec![
+            trace(&initial, |d, o| selection_sort::sort_traced(d, o)),
+            trace(&initial, |d, o| insertion_sort::sort_traced(d, o)),
+            trace(&initial, |d, o| shellsort::sort_traced(d, o))
+        ];
+        for events in traces {
+            assert_eq!(sorted, frames(&initial, &events).last().unwrap().values);
+        }
+    }
+
+    #[test]
+    fn selection_compares() {
+        // N^2 / 2 compares and N exchanges whatever the input
+        let events = trace(&[1, 2, 3, 4, 5, 6], |d, o| selection_sort::sort_traced(d, o));
+        let compares = events.iter().filter(|e| matches!(e, Event::Compare(..))).count();
+        let exchanges = events.iter().filter(|e| matches!(e, Event::Exchange(..))).count();
+        assert_eq!(15, compares);
+        assert_eq!(5, exchanges);
+    }
+
+    #[test]
+    fn ascii() {
+        let frame = Frame { values: vec![4, 1, 2], event: Some(Event::Compare(1, 2)) };
+        assert_eq!("#\n#\n# ?\n#??\ncompare 1 2\n", render_ascii(&frame, 4));
+
+        // scaled to the height
+        let frame = Frame { values: vec![10, 5, 0], event: None };
+        assert_eq!("#\n##\n", render_ascii(&frame, 2));
+    }
+
+    #[test]
+    fn svg() {
+        let dir = std::env::temp_dir().join(format!("elementary_sort_trace_{}", std::process::id()));
+        let written = write_svg_frames(&dir, &[2, 1], &[Event::Compare(1, 0), Event::Exchange(1, 0)]).unwrap();
+        assert_eq!(3, written);
+
+        let last = fs::read_to_string(dir.join("frame_00002.svg")).unwrap();
+        assert!(last.starts_with("<svg"));
+        assert_eq!(2, last.matches("crimson").count());
+        assert!(last.contains("<title>exchange 1 0</title>"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
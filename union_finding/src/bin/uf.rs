@@ -0,0 +1,141 @@
+//!
+//! Dynamic connectivity client reading the algs4 input format (tinyUF.txt, mediumUF.txt, largeUF.txt):
+//! the number of sites on the first line followed by pairs of sites.
+//! It prints every pair that connects two different components, then the number of components.
+//!
+//! ```text
+//! uf [--quickfind | --quickunion | --weighting] [FILE]
+//! ```
+//!
+//! The input is read from stdin when FILE is missing or "-". Default algorithm is weighting.
+
+use std::env;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::process;
+use std::time::Instant;
+
+use union_finding::site::SiteIndex;
+use union_finding::{quickfind, quickunion, weighting, UnionFind};
+
+const USAGE: &str = "usage: uf [--quickfind | --quickunion | --weighting] [FILE]";
+
+/// Run the client on the input, writing the new connections to out. Return the number of components
+fn run<U: UnionFind>(input: &str, out: &mut impl Write) -> Result<usize, String> {
+    let mut tokens = input.split_whitespace();
+
+    let n = tokens.next().ok_or("empty input, the number of sites is missing")?;
+    let n: usize = n.parse().map_err(|_| format!("invalid number of sites: {}", n))?;
+    // the algorithms run with their default u32 index
+    if n > <u32 as SiteIndex>::MAX {
+        return Err(format!("too many sites: {}, at most {} are supported", n, <u32 as SiteIndex>::MAX));
+    }
+
+    let mut uf = U::new(n);
+
+    while let Some(p) = tokens.next() {
+        let q = tokens.next().ok_or_else(|| format!("site {} has no pair", p))?;
+        let p: usize = p.parse().map_err(|_| format!("invalid site: {}", p))?;
+        let q: usize = q.parse().map_err(|_| format!("invalid site: {}", q))?;
+
+        if uf.try_connected(p, q).map_err(|e| e.to_string())? {continue}
+
+        uf.union(p, q);
+        writeln!(out, "{} {}", p, q).map_err(|e| e.to_string())?;
+    }
+
+    Ok(uf.count())
+}
+
+fn main() {
+    let mut algorithm = "weighting";
+    let mut path = None;
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--quickfind" => algorithm = "quickfind",
+            "--quickunion" => algorithm = "quickunion",
+            "--weighting" => algorithm = "weighting",
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            },
+            _ if arg.starts_with("--") => {
+                eprintln!("unknown option {}\n{}", arg, USAGE);
+                process::exit(2);
+            },
+            _ => path = Some(arg)
+        }
+    }
+
+    let input = match path.as_deref() {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).map(|_| input)
+        },
+        Some(path) => fs::read_to_string(path)
+    };
+    let input = input.unwrap_or_else(|e| {
+        eprintln!("cannot read the input: {}", e);
+        process::exit(1);
+    });
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+
+    let start = Instant::now();
+    let count = match algorithm {
+        "quickfind" => run::<quickfind::UT>(&input, &mut out),
+        "quickunion" => run::<quickunion::UT>(&input, &mut out),
+        _ => run::<weighting::UT>(&input, &mut out)
+    };
+    let elapsed = start.elapsed();
+
+    match count {
+        Ok(count) => {
+            let _ = writeln!(out, "{} components", count);
+            let _ = writeln!(out, "{} elapsed time {:.3}s", algorithm, elapsed.as_secs_f64());
+        },
+        Err(e) => {
+            let _ = out.flush();
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    const TINY_UF: &str = "10\n4 3\n3 8\n6 5\n9 4\n2 1\n8 9\n5 0\n7 2\n6 1\n1 0\n6 7\n";
+
+    fn connections<U: UnionFind>(input: &str) -> (Result<usize, String>, String) {
+        let mut out = Vec::new();
+        let count = run::<U>(input, &mut out);
+        (count, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    pub fn tiny_uf(){
+        let expected = "4 3\n3 8\n6 5\n9 4\n2 1\n5 0\n7 2\n6 1\n";
+
+        assert_eq!((Ok(2), expected.to_string()), connections::<quickfind::UT>(TINY_UF));
+        assert_eq!((Ok(2), expected.to_string()), connections::<quickunion::UT>(TINY_UF));
+        assert_eq!((Ok(2), expected.to_string()), connections::<weighting::UT>(TINY_UF));
+    }
+
+    #[test]
+    pub fn malformed_input(){
+        assert!(connections::<weighting::UT>("").0.is_err());
+        assert!(connections::<weighting::UT>("ten").0.is_err());
+        assert!(connections::<weighting::UT>("3\n0 1\n2").0.is_err());
+        assert_eq!(Err("site 5 is out of range, there are 3 sites".to_string()), connections::<weighting::UT>("3\n0 5\n").0);
+        assert_eq!(
+            Err("too many sites: 99999999999, at most 4294967295 are supported".to_string()),
+            connections::<quickfind::UT>("99999999999\n0 1\n").0
+        );
+    }
+
+}
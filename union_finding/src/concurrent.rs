@@ -0,0 +1,222 @@
+//!
+//! Lock-free union-find that can be shared between threads: `union` and `connected` take `&self`,
+//! so there is no need to serialize them behind a mutex.
+//!
+//! Parent pointers are atomics and every change is a compare-and-swap. A root is linked only if it
+//! is still a root when the swap happens, otherwise the operation is retried from the new roots.
+//! Instead of weights, roots are linked following a random total order on the sites (Jayanti and Tarjan),
+//! which keeps the trees of logarithmic expected height without having to update two words at once.
+//! Paths are halved during find as in weighting::UT.
+//!
+//! ```rust
+//!     # use union_finding::concurrent::UT;
+//!     let ut = UT::new(4);
+//!     std::thread::scope(|s| {
+//!         s.spawn(|| ut.union(0, 1));
+//!         s.spawn(|| ut.union(2, 3));
+//!     });
+//!     assert!(ut.connected(0, 1));
+//!     assert!(!ut.connected(1, 2));
+//!     assert_eq!(2, ut.count());
+//! ```
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::UnionFind;
+
+pub struct UT {
+    id: Vec<AtomicUsize>,
+    // random order used to choose which root is linked below the other
+    priority: Vec<u64>,
+    count: AtomicUsize
+}
+
+impl UT{
+
+    /// Return a new instance with n sites, each one in its own component
+    pub fn new(n: usize) -> UT{
+        UT{
+            id: (0..n).map(AtomicUsize::new).collect(),
+            priority: (0..n).map(|_| rand::random()).collect(),
+            count: AtomicUsize::new(n)
+        }
+    }
+
+    /// Return the current root of i
+    pub fn root(&self, mut i: usize) -> usize{
+        loop {
+            let parent = self.id[i].load(Ordering::Acquire);
+            if parent == i {return i}
+
+            let grandparent = self.id[parent].load(Ordering::Acquire);
+            if parent != grandparent {
+                // halving. If another thread moved i in the meantime its pointer is already higher in the tree
+                let _ = self.id[i].compare_exchange_weak(parent, grandparent, Ordering::AcqRel, Ordering::Acquire);
+            }
+            i = grandparent;
+        }
+    }
+
+    /// Check if p and q are connected
+    pub fn connected(&self, p: usize, q: usize) -> bool{
+        loop {
+            let pi = self.root(p);
+            let qi = self.root(q);
+
+            if pi == qi {return true}
+            // if pi is still a root, the two components were disjoint when qi was read as a root
+            if self.id[pi].load(Ordering::Acquire) == pi {return false}
+        }
+    }
+
+    /// Merge the components of p and q. Return false if they were already connected
+    pub fn union(&self, p: usize, q: usize) -> bool{
+        loop {
+            let mut pi = self.root(p);
+            let mut qi = self.root(q);
+
+            if pi == qi {return false}
+
+            // link the lower root below the higher one
+            if (self.priority[pi], pi) > (self.priority[qi], qi) {
+                std::mem::swap(&mut pi, &mut qi);
+            }
+            if self.id[pi].compare_exchange(pi, qi, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+                self.count.fetch_sub(1, Ordering::AcqRel);
+                return true;
+            }
+        }
+    }
+
+    /// Return the number of components
+    pub fn count(&self) -> usize{
+        self.count.load(Ordering::Acquire)
+    }
+
+}
+
+impl UnionFind for UT{
+
+    fn new(n: usize) -> UT{
+        UT::new(n)
+    }
+
+    fn union(&mut self, p: usize, q: usize){
+        UT::union(self, p, q);
+    }
+
+    fn find(&mut self, p: usize) -> usize{
+        self.root(p)
+    }
+
+    fn connected(&mut self, p: usize, q: usize) -> bool{
+        UT::connected(self, p, q)
+    }
+
+    fn count(&self) -> usize{
+        UT::count(self)
+    }
+
+    fn len(&self) -> usize{
+        self.id.len()
+    }
+
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+    use crate::weighting;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use std::thread;
+
+    // label every site with the smallest member of its component
+    fn canonical<F: FnMut(usize) -> usize>(n: usize, root: F) -> Vec<usize>{
+        let mut smallest = vec![usize::MAX; n];
+        let roots: Vec<usize> = (0..n).map(root).collect();
+        for i in 0..n{
+            smallest[roots[i]] = smallest[roots[i]].min(i);
+        }
+        roots.iter().map(|&r| smallest[r]).collect()
+    }
+
+    #[test]
+    pub fn sequential(){
+        let ut = UT::new(10);
+        assert!(ut.union(4, 3));
+        assert!(ut.union(3, 8));
+        assert!(!ut.union(8, 4));
+
+        assert!(ut.connected(4, 8));
+        assert!(!ut.connected(4, 5));
+        assert_eq!(8, ut.count());
+    }
+
+    #[test]
+    pub fn stress_against_weighting(){
+        let n = 2000;
+        let threads = 8;
+        let unions = 300;
+        let ut = UT::new(n);
+
+        let pairs: Vec<Vec<(usize, usize)>> = (0..threads).map(|t| {
+            let mut rng = StdRng::seed_from_u64(t);
+            (0..unions).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n))).collect()
+        }).collect();
+
+        let merged: usize = thread::scope(|s| {
+            let handles: Vec<_> = pairs.iter().map(|pairs| {
+                let ut = &ut;
+                s.spawn(move || pairs.iter().filter(|&&(p, q)| ut.union(p, q)).count())
+            }).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+
+        let mut sequential = weighting::UT::new(n);
+        for &(p, q) in pairs.iter().flatten(){
+            sequential.union(p, q);
+        }
+
+        assert_eq!(sequential.count(), ut.count());
+        assert_eq!(n - merged, ut.count());
+        assert_eq!(canonical(n, |i| sequential.root(i)), canonical(n, |i| ut.root(i)));
+    }
+
+    #[test]
+    pub fn concurrent_queries_are_monotonic(){
+        let n = 500;
+        let ut = UT::new(n);
+
+        thread::scope(|s| {
+            for t in 0..4{
+                let ut = &ut;
+                s.spawn(move || {
+                    let mut rng = StdRng::seed_from_u64(100 + t);
+                    for _ in 0..2000{
+                        ut.union(rng.gen_range(0..n), rng.gen_range(0..n));
+                    }
+                });
+            }
+            for t in 0..4{
+                let ut = &ut;
+                s.spawn(move || {
+                    let mut rng = StdRng::seed_from_u64(200 + t);
+                    let mut seen = Vec::new();
+                    for _ in 0..2000{
+                        let (p, q) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                        if ut.connected(p, q) {seen.push((p, q))}
+                    }
+                    // unions are never undone
+                    for (p, q) in seen{
+                        assert!(ut.connected(p, q));
+                    }
+                });
+            }
+        });
+
+        assert!(ut.count() >= 1);
+    }
+
+}
@@ -0,0 +1,71 @@
+//!
+//! Array-access counter embedded in quickfind::UT, quickunion::UT and weighting::UT.
+//!
+//! Without the `instrument` feature it is a zero-sized type whose methods do nothing, so the
+//! union-find types pay nothing for it. With the feature the counts are atomics, which keeps the
+//! types Sync and lets queries taking &self count as well.
+
+#[cfg(feature = "instrument")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "instrument")]
+use crate::instrument::AccessStats;
+
+#[cfg(feature = "instrument")]
+#[derive(Debug, Default)]
+pub(crate) struct Counter {
+    enabled: bool,
+    reads: AtomicU64,
+    writes: AtomicU64
+}
+
+#[cfg(feature = "instrument")]
+impl Counter {
+
+    #[inline]
+    pub(crate) fn read(&self, n: u64) {
+        if self.enabled {
+            self.reads.fetch_add(n, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn write(&self, n: u64) {
+        if self.enabled {
+            self.writes.fetch_add(n, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self.reads.get_mut() = 0;
+        *self.writes.get_mut() = 0;
+    }
+
+    pub(crate) fn stats(&self, max_height: usize) -> AccessStats {
+        AccessStats {
+            reads: self.reads.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+            max_height
+        }
+    }
+
+}
+
+#[cfg(not(feature = "instrument"))]
+#[derive(Debug, Default)]
+pub(crate) struct Counter {}
+
+#[cfg(not(feature = "instrument"))]
+impl Counter {
+
+    #[inline(always)]
+    pub(crate) fn read(&self, _n: u64) {}
+
+    #[inline(always)]
+    pub(crate) fn write(&self, _n: u64) {}
+
+}
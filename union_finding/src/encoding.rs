@@ -0,0 +1,290 @@
+//!
+//! Compact binary encoding of the union-find state, so long jobs can be checkpointed and restored.
+//!
+//! Each of quickfind::UT, quickunion::UT and weighting::UT has `to_bytes` and `from_bytes`.
+//! The encoding is a small header followed by the id array, each entry stored with the width of the index type.
+//! Sizes of the weighted trees are not stored, they are recomputed from the parents.
+//!
+//! ```rust
+//!     # use union_finding::weighting::UT;
+//!     let mut ut = UT::new(10);
+//!     ut.union(1, 2);
+//!     let bytes = ut.to_bytes();
+//!
+//!     let mut restored: UT = UT::from_bytes(&bytes).unwrap();
+//!     assert!(restored.connected(1, 2));
+//!     assert_eq!(9, restored.count());
+//! ```
+//!
+//! Decoding never trusts the input: parents out of range, cycles in the parent array and
+//! inconsistent labels are rejected, so a restored instance always satisfies the invariants.
+//!
+//! With the `serde` feature the three types also implement `Serialize` and `Deserialize` through the same encoding.
+
+use std::error::Error;
+use std::fmt;
+use std::mem;
+
+use crate::site::SiteIndex;
+
+const MAGIC: &[u8; 2] = b"UF";
+const VERSION: u8 = 1;
+
+pub(crate) const QUICKFIND: u8 = 1;
+pub(crate) const QUICKUNION: u8 = 2;
+pub(crate) const WEIGHTING: u8 = 3;
+
+/// Returned when bytes cannot be decoded into a union-find
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input ended before the end of the encoded state
+    Truncated,
+    /// There are bytes after the end of the encoded state
+    TrailingBytes,
+    /// Not an encoding of this union-find type, or of this version
+    Header,
+    /// The number of sites does not fit in the index type
+    TooManySites(u64),
+    /// A site points to a parent which is not a site
+    OutOfRange { site: usize, parent: u64 },
+    /// Following the parents from this site never reaches a root
+    Cycle { site: usize },
+    /// The component id of this site is not the id of a component
+    InvalidLabel { site: usize },
+    /// The rank of this site is not consistent with its tree
+    InvalidRank { site: usize }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "unexpected end of input"),
+            DecodeError::TrailingBytes => write!(f, "unexpected bytes after the end of input"),
+            DecodeError::Header => write!(f, "invalid header"),
+            DecodeError::TooManySites(n) => write!(f, "{} sites do not fit in the index type", n),
+            DecodeError::OutOfRange { site, parent } => write!(f, "site {} points to {} which is out of range", site, parent),
+            DecodeError::Cycle { site } => write!(f, "site {} is in a cycle", site),
+            DecodeError::InvalidLabel { site } => write!(f, "site {} has an invalid component id", site),
+            DecodeError::InvalidRank { site } => write!(f, "site {} has an invalid rank", site)
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// Write the header, the strategy bytes and the id array
+pub(crate) fn encode<I: SiteIndex>(kind: u8, extra: &[u8], id: &[I]) -> Vec<u8> {
+    let width = mem::size_of::<I>();
+    let mut bytes = Vec::with_capacity(5 + extra.len() + 8 + mem::size_of_val(id));
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.push(kind);
+    bytes.push(width as u8);
+    bytes.extend_from_slice(extra);
+    bytes.extend_from_slice(&(id.len() as u64).to_le_bytes());
+    for &i in id {
+        bytes.extend_from_slice(&(i.to_usize() as u64).to_le_bytes()[..width]);
+    }
+
+    bytes
+}
+
+/// Cursor over the bytes being decoded
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8]
+}
+
+impl<'a> Reader<'a> {
+
+    /// Check the header and return a reader positioned after it, along with the width of the entries
+    pub(crate) fn new(bytes: &'a [u8], kind: u8) -> Result<(Reader<'a>, usize), DecodeError> {
+        let mut reader = Reader { bytes };
+        let header = reader.take(5)?;
+
+        if &header[..2] != MAGIC || header[2] != VERSION || header[3] != kind {
+            return Err(DecodeError::Header);
+        }
+        let width = header[4] as usize;
+        if ![1, 2, 4, 8].contains(&width) {
+            return Err(DecodeError::Header);
+        }
+
+        Ok((reader, width))
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < n {
+            return Err(DecodeError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn byte(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn uint(&mut self, width: usize) -> Result<u64, DecodeError> {
+        let mut le = [0; 8];
+        le[..width].copy_from_slice(self.take(width)?);
+        Ok(u64::from_le_bytes(le))
+    }
+
+    /// Read the id array, checking that every entry is one of the sites and that they fit in I
+    pub(crate) fn id<I: SiteIndex>(&mut self, width: usize) -> Result<Vec<I>, DecodeError> {
+        let n = self.uint(8)?;
+        if n > I::MAX as u64 {
+            return Err(DecodeError::TooManySites(n));
+        }
+        // do not trust n for the allocation before knowing the input is long enough
+        if (self.bytes.len() as u64) < n.saturating_mul(width as u64) {
+            return Err(DecodeError::Truncated);
+        }
+
+        let n = n as usize;
+        let mut id = Vec::with_capacity(n);
+        for site in 0..n {
+            let parent = self.uint(width)?;
+            if parent >= n as u64 {
+                return Err(DecodeError::OutOfRange { site, parent });
+            }
+            id.push(I::from_usize(parent as usize));
+        }
+
+        Ok(id)
+    }
+
+    /// Fail if something is left
+    pub(crate) fn finish(self) -> Result<(), DecodeError> {
+        if self.bytes.is_empty() { Ok(()) } else { Err(DecodeError::TrailingBytes) }
+    }
+
+}
+
+/// Check that the parents form a forest and return the root of every site
+pub(crate) fn roots<I: SiteIndex>(id: &[I]) -> Result<Vec<usize>, DecodeError> {
+    const UNVISITED: usize = usize::MAX;
+    const IN_PATH: usize = usize::MAX - 1;
+
+    let mut root = vec![UNVISITED; id.len()];
+    let mut path = Vec::new();
+
+    for site in 0..id.len() {
+        let mut i = site;
+        // walk up until a root or a site whose root is already known
+        while root[i] == UNVISITED && id[i].to_usize() != i {
+            root[i] = IN_PATH;
+            path.push(i);
+            i = id[i].to_usize();
+        }
+        if root[i] == IN_PATH {
+            return Err(DecodeError::Cycle { site });
+        }
+
+        let r = if root[i] == UNVISITED { i } else { root[i] };
+        root[i] = r;
+        for j in path.drain(..) {
+            root[j] = r;
+        }
+    }
+
+    Ok(root)
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+    use serde::ser::{Serialize, Serializer};
+
+    use crate::site::SiteIndex;
+    use crate::{quickfind, quickunion, weighting};
+
+    struct BytesVisitor<T>(PhantomData<T>);
+
+    macro_rules! impl_serde {
+        ($($ut:ident),*) => {
+            $(
+                impl<I: SiteIndex> Serialize for $ut::UT<I> {
+                    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                        serializer.serialize_bytes(&self.to_bytes())
+                    }
+                }
+
+                impl<'de, I: SiteIndex> Visitor<'de> for BytesVisitor<$ut::UT<I>> {
+                    type Value = $ut::UT<I>;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "the binary encoding of {}::UT", stringify!($ut))
+                    }
+
+                    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+                        $ut::UT::from_bytes(bytes).map_err(E::custom)
+                    }
+
+                    // self-describing formats without a bytes type, such as JSON, write them as a sequence
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                        while let Some(b) = seq.next_element()? {
+                            bytes.push(b);
+                        }
+                        self.visit_bytes(&bytes)
+                    }
+                }
+
+                impl<'de, I: SiteIndex> Deserialize<'de> for $ut::UT<I> {
+                    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                        deserializer.deserialize_bytes(BytesVisitor::<$ut::UT<I>>(PhantomData))
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_serde!(quickfind, quickunion, weighting);
+
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    #[test]
+    pub fn forest(){
+        assert_eq!(Ok(vec![0, 0, 0, 3]), roots(&[0u32, 0, 1, 3]));
+        assert_eq!(Ok(vec![]), roots::<u32>(&[]));
+    }
+
+    #[test]
+    pub fn cycles(){
+        assert_eq!(Err(DecodeError::Cycle { site: 0 }), roots(&[1u32, 0]));
+        // a cycle not containing the first site
+        assert_eq!(Err(DecodeError::Cycle { site: 1 }), roots(&[0u8, 2, 3, 1]));
+    }
+
+    #[test]
+    pub fn header(){
+        let bytes = encode(QUICKUNION, &[], &[0u16, 0]);
+        assert_eq!(Err(DecodeError::Header), Reader::new(&bytes, QUICKFIND).map(|_| ()));
+        assert_eq!(Err(DecodeError::Truncated), Reader::new(&bytes[..3], QUICKUNION).map(|_| ()));
+
+        let (mut reader, width) = Reader::new(&bytes, QUICKUNION).unwrap();
+        assert_eq!(2, width);
+        assert_eq!(Ok(vec![0u64, 0]), reader.id::<u64>(width));
+        assert_eq!(Ok(()), reader.finish());
+    }
+
+    #[test]
+    pub fn too_many_sites(){
+        let bytes = encode(QUICKUNION, &[], &(0..300u32).collect::<Vec<_>>());
+        let (mut reader, width) = Reader::new(&bytes, QUICKUNION).unwrap();
+        assert_eq!(Err(DecodeError::TooManySites(300)), reader.id::<u8>(width));
+    }
+
+}
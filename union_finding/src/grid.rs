@@ -0,0 +1,261 @@
+//!
+//! Connected-component labeling of 2D grids, such as binary images or occupancy grids.
+//! Neighbouring cells are united in a weighted quick union, then every component gets a label.
+//!
+//! Cells are given row by row, so the cell (x, y) is at index y * width + x.
+//!
+//! ```rust
+//!     # use union_finding::grid::{label_binary, Connectivity};
+//!     let image = [
+//!         true,  true,  false,
+//!         false, false, false,
+//!         false, true,  true,
+//!     ];
+//!     let labeling = label_binary(3, 3, &image, Connectivity::Four);
+//!     assert_eq!(2, labeling.components.len());
+//!     assert_eq!(vec![Some(0), Some(0), None, None, None, None, None, Some(1), Some(1)], labeling.labels);
+//! ```
+
+use crate::weighting::UT;
+
+/// Which neighbours of a cell are connected to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Left, right, up and down
+    Four,
+    /// Diagonals as well
+    Eight
+}
+
+/// Smallest rectangle containing a component, bounds included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min_x: usize,
+    pub min_y: usize,
+    pub max_x: usize,
+    pub max_y: usize
+}
+
+impl BoundingBox {
+
+    pub fn width(&self) -> usize {
+        self.max_x - self.min_x + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.max_y - self.min_y + 1
+    }
+
+}
+
+/// A labelled component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Component {
+    /// Number of cells
+    pub size: usize,
+    pub bounding_box: BoundingBox
+}
+
+/// Result of a labeling. Labels are numbered from 0 in the order their first cell appears
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Labeling {
+    pub width: usize,
+    pub height: usize,
+    /// Label of each cell, None for cells that are not labelled (background)
+    pub labels: Vec<Option<usize>>,
+    /// Components indexed by label
+    pub components: Vec<Component>
+}
+
+impl Labeling {
+
+    /// Return the label of the cell (x, y)
+    pub fn label(&self, x: usize, y: usize) -> Option<usize> {
+        assert!(x < self.width && y < self.height, "cell ({}, {}) is outside the {}x{} grid", x, y, self.width, self.height);
+        self.labels[y * self.width + x]
+    }
+
+}
+
+/// Label the true cells of a binary grid, false cells are background
+pub fn label_binary(width: usize, height: usize, cells: &[bool], connectivity: Connectivity) -> Labeling {
+    label_by(width, height, cells, connectivity, |c| *c, |_, _| true)
+}
+
+/// Label every cell of the grid, neighbours with the same color are in the same component
+pub fn label_colors<T: PartialEq>(width: usize, height: usize, cells: &[T], connectivity: Connectivity) -> Labeling {
+    label_by(width, height, cells, connectivity, |_| true, |a, b| a == b)
+}
+
+/// General labeling: cells for which `foreground` is false are background,
+/// two neighbouring foreground cells are connected if `same` is true for them
+pub fn label_by<T, F, S>(width: usize, height: usize, cells: &[T], connectivity: Connectivity, foreground: F, same: S) -> Labeling
+where
+    F: Fn(&T) -> bool,
+    S: Fn(&T, &T) -> bool
+{
+    assert_eq!(width * height, cells.len(), "a {}x{} grid needs {} cells", width, height, width * height);
+
+    let mut uf = UT::new(cells.len());
+    let fg: Vec<bool> = cells.iter().map(foreground).collect();
+
+    // looking only at the neighbours already visited is enough to see every pair once
+    let mut previous: Vec<(isize, isize)> = vec![(-1, 0), (0, -1)];
+    if connectivity == Connectivity::Eight {
+        previous.push((-1, -1));
+        previous.push((1, -1));
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            if !fg[i] {continue}
+
+            for &(dx, dy) in &previous {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                if nx < 0 || ny < 0 || nx >= width as isize {continue}
+
+                let j = ny as usize * width + nx as usize;
+                if fg[j] && same(&cells[i], &cells[j]) {
+                    uf.union(i, j);
+                }
+            }
+        }
+    }
+
+    // label of each root, given when its first cell is met
+    let mut root_label: Vec<Option<usize>> = vec![None; cells.len()];
+    let mut labels = vec![None; cells.len()];
+    let mut components: Vec<Component> = Vec::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            if !fg[i] {continue}
+
+            let r = uf.root(i);
+            let label = match root_label[r] {
+                Some(label) => {
+                    let c = &mut components[label];
+                    c.size += 1;
+                    c.bounding_box.min_x = c.bounding_box.min_x.min(x);
+                    c.bounding_box.max_x = c.bounding_box.max_x.max(x);
+                    c.bounding_box.max_y = y;
+                    label
+                },
+                None => {
+                    components.push(Component {
+                        size: 1,
+                        bounding_box: BoundingBox { min_x: x, min_y: y, max_x: x, max_y: y }
+                    });
+                    root_label[r] = Some(components.len() - 1);
+                    components.len() - 1
+                }
+            };
+            labels[i] = Some(label);
+        }
+    }
+
+    Labeling {
+        width,
+        height,
+        labels,
+        components
+    }
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    fn parse(rows: &[&str]) -> (usize, usize, Vec<bool>) {
+        let cells = rows.iter().flat_map(|r| r.chars().map(|c| c == '#')).collect();
+        (rows[0].len(), rows.len(), cells)
+    }
+
+    #[test]
+    pub fn four_connectivity(){
+        let (w, h, cells) = parse(&[
+            "#..#",
+            ".#.#",
+            "..##",
+        ]);
+        let labeling = label_binary(w, h, &cells, Connectivity::Four);
+
+        assert_eq!(3, labeling.components.len());
+        assert_eq!(Some(0), labeling.label(0, 0));
+        assert_eq!(Some(1), labeling.label(3, 0));
+        assert_eq!(Some(2), labeling.label(1, 1));
+        assert_eq!(Some(1), labeling.label(2, 2));
+        assert_eq!(None, labeling.label(1, 0));
+        assert_eq!(4, labeling.components[1].size);
+        assert_eq!(BoundingBox { min_x: 2, min_y: 0, max_x: 3, max_y: 2 }, labeling.components[1].bounding_box);
+    }
+
+    #[test]
+    pub fn eight_connectivity(){
+        let (w, h, cells) = parse(&[
+            "#..#",
+            ".#.#",
+            "..##",
+        ]);
+        let labeling = label_binary(w, h, &cells, Connectivity::Eight);
+
+        assert_eq!(1, labeling.components.len());
+        assert_eq!(6, labeling.components[0].size);
+        assert_eq!(4, labeling.components[0].bounding_box.width());
+        assert_eq!(3, labeling.components[0].bounding_box.height());
+    }
+
+    #[test]
+    pub fn anti_diagonal(){
+        // the (1, -1) neighbour joins cells going down to the left
+        let (w, h, cells) = parse(&[
+            "..#",
+            ".#.",
+            "#..",
+        ]);
+        assert_eq!(3, label_binary(w, h, &cells, Connectivity::Four).components.len());
+        assert_eq!(1, label_binary(w, h, &cells, Connectivity::Eight).components.len());
+    }
+
+    #[test]
+    pub fn u_shape(){
+        // both arms get the same label even if they are seen apart first
+        let (w, h, cells) = parse(&[
+            "#.#",
+            "#.#",
+            "###",
+        ]);
+        let labeling = label_binary(w, h, &cells, Connectivity::Four);
+        assert_eq!(1, labeling.components.len());
+        assert_eq!(labeling.label(0, 0), labeling.label(2, 0));
+    }
+
+    #[test]
+    pub fn colors(){
+        let cells = [
+            1, 1, 2,
+            3, 1, 2,
+            3, 3, 2,
+        ];
+        let labeling = label_colors(3, 3, &cells, Connectivity::Four);
+
+        assert_eq!(vec![Some(0), Some(0), Some(1), Some(2), Some(0), Some(1), Some(2), Some(2), Some(1)], labeling.labels);
+        assert_eq!(vec![3, 3, 3], labeling.components.iter().map(|c| c.size).collect::<Vec<_>>());
+    }
+
+    #[test]
+    pub fn empty_grid(){
+        let labeling = label_binary(0, 0, &[], Connectivity::Eight);
+        assert!(labeling.components.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn wrong_size(){
+        label_binary(2, 2, &[true; 3], Connectivity::Four);
+    }
+
+}
@@ -0,0 +1,213 @@
+//!
+//! Observe the cost of the union-find algorithms instead of trusting the comments.
+//!
+//! Needs the `instrument` feature. When instrumentation is enabled, quickfind::UT, quickunion::UT and weighting::UT count every
+//! read and write of their arrays. Along with the height of the tallest tree this is enough to see
+//! quick find paying N accesses per union and quick union growing tall trees.
+//!
+//! ```rust
+//!     # use union_finding::instrument::Instrumented;
+//!     # use union_finding::quickfind::UT;
+//!     let mut ut = UT::new(10);
+//!     ut.set_instrumented(true);
+//!     ut.union(0, 1);
+//!     // two reads for the ids, then a pass over the array changing one entry
+//!     assert_eq!(12, ut.access_stats().reads);
+//!     assert_eq!(1, ut.access_stats().writes);
+//! ```
+//!
+//! The doubling experiment runs N random unions on N sites for growing N, the ratio between
+//! consecutive rounds tells the order of growth (about 2 for linear, 4 for quadratic).
+//!
+//! ```rust
+//!     # use union_finding::instrument::doubling;
+//!     # use union_finding::weighting::UT;
+//!     let rows = doubling::<UT, _>(64, 4, &mut rand::thread_rng());
+//!     assert_eq!(vec![64, 128, 256, 512], rows.iter().map(|r| r.n).collect::<Vec<_>>());
+//! ```
+
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::UnionFind;
+
+/// Array accesses made since instrumentation was enabled or last reset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AccessStats {
+    pub reads: u64,
+    pub writes: u64,
+    /// Height of the tallest tree, 0 when every site is a root
+    pub max_height: usize
+}
+
+impl AccessStats {
+
+    pub fn accesses(&self) -> u64 {
+        self.reads + self.writes
+    }
+
+}
+
+/// Union-find whose array accesses can be counted
+pub trait Instrumented: UnionFind {
+    /// Start or stop counting. Counts are kept when stopping
+    fn set_instrumented(&mut self, enabled: bool);
+
+    /// Return the counts along with the current height. Computing the height is not counted
+    fn access_stats(&self) -> AccessStats;
+
+    /// Set the counts back to zero
+    fn reset_access_stats(&mut self);
+}
+
+/// Height of the tallest tree of a parent array, without compressing anything
+pub(crate) fn max_height<T: Copy, F: Fn(T) -> usize>(id: &[T], index: F) -> usize {
+    // depth of each site, computed once by walking up to a site already known
+    let mut depth: Vec<Option<usize>> = vec![None; id.len()];
+    let mut path = Vec::new();
+    let mut max = 0;
+
+    for site in 0..id.len() {
+        let mut i = site;
+        while depth[i].is_none() {
+            let parent = index(id[i]);
+            if parent == i {
+                depth[i] = Some(0);
+                break;
+            }
+            path.push(i);
+            i = parent;
+        }
+
+        let mut d = depth[i].unwrap_or(0);
+        while let Some(j) = path.pop() {
+            d += 1;
+            depth[j] = Some(d);
+        }
+        max = max.max(depth[site].unwrap_or(0));
+    }
+
+    max
+}
+
+/// One round of the doubling experiment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DoublingRow {
+    /// Number of sites, and of random unions
+    pub n: usize,
+    pub stats: AccessStats,
+    pub elapsed: Duration,
+    /// Accesses of this round divided by the accesses of the previous one
+    pub ratio: Option<f64>
+}
+
+/// Run `rounds` rounds of N random unions on N sites, starting from N = start and doubling N every round
+pub fn doubling<U: Instrumented, R: Rng>(start: usize, rounds: usize, rng: &mut R) -> Vec<DoublingRow> {
+    assert!(start > 0, "the experiment needs at least one site");
+
+    let mut rows: Vec<DoublingRow> = Vec::with_capacity(rounds);
+    let mut n = start;
+
+    for _ in 0..rounds {
+        let pairs: Vec<(usize, usize)> = (0..n).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n))).collect();
+
+        let mut uf = U::new(n);
+        uf.set_instrumented(true);
+        let begin = Instant::now();
+        for (p, q) in pairs {
+            uf.union(p, q);
+        }
+        let elapsed = begin.elapsed();
+        let stats = uf.access_stats();
+
+        let ratio = rows.last().map(|previous| stats.accesses() as f64 / previous.stats.accesses() as f64);
+        rows.push(DoublingRow { n, stats, elapsed, ratio });
+        n *= 2;
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+    use crate::{quickfind, quickunion, weighting};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    pub fn height(){
+        assert_eq!(0, max_height(&[0, 1, 2], |i| i));
+        assert_eq!(3, max_height(&[0, 0, 1, 2, 0], |i| i));
+        assert_eq!(0, max_height::<usize, _>(&[], |i| i));
+    }
+
+    #[test]
+    pub fn disabled_by_default(){
+        let mut ut = weighting::UT::new(4);
+        ut.union(0, 1);
+        assert_eq!(0, ut.access_stats().accesses());
+
+        ut.set_instrumented(true);
+        ut.union(2, 3);
+        assert!(ut.access_stats().accesses() > 0);
+
+        ut.reset_access_stats();
+        assert_eq!(0, ut.access_stats().accesses());
+        assert_eq!(1, ut.access_stats().max_height);
+    }
+
+    #[test]
+    pub fn quickunion_grows_tall_trees(){
+        // linking each root below the next makes a single path
+        let mut ut = quickunion::UT::new(64);
+        for i in 0..63{
+            ut.union(i, i + 1);
+        }
+        assert_eq!(63, ut.access_stats().max_height);
+
+        let mut ut = weighting::UT::new(64);
+        for i in 0..63{
+            ut.union(i, i + 1);
+        }
+        assert!(ut.access_stats().max_height <= 6);
+    }
+
+    #[test]
+    pub fn instrumented_types_are_sync(){
+        fn assert_sync<T: Sync>(){}
+        assert_sync::<quickfind::UT>();
+        assert_sync::<quickunion::UT>();
+        assert_sync::<weighting::UT>();
+    }
+
+    #[test]
+    pub fn union_by_size_reads(){
+        // two roots found with one read each, then sizes, max and min read for the new root,
+        // and the sizes read again to choose which root goes on top
+        let mut ut = weighting::UT::new(2);
+        ut.set_instrumented(true);
+        ut.union(0, 1);
+        assert_eq!(10, ut.access_stats().reads);
+        assert_eq!(4, ut.access_stats().writes);
+    }
+
+    #[test]
+    pub fn orders_of_growth(){
+        let mut rng = StdRng::seed_from_u64(17);
+
+        // quick find touches the whole array at every union: N unions cost N^2
+        let rows = doubling::<quickfind::UT, _>(128, 4, &mut rng);
+        let ratio = rows.last().unwrap().ratio.unwrap();
+        assert!(ratio > 3.5 && ratio < 4.5, "quickfind ratio {}", ratio);
+
+        // weighted quick union is almost linear
+        let rows = doubling::<weighting::UT, _>(128, 4, &mut rng);
+        let ratio = rows.last().unwrap().ratio.unwrap();
+        assert!(ratio > 1.5 && ratio < 2.7, "weighting ratio {}", ratio);
+        assert!(rows[0].ratio.is_none());
+    }
+
+}
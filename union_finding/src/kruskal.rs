@@ -0,0 +1,176 @@
+//!
+//! Minimum spanning tree with Kruskal's algorithm: consider the edges in ascending order of weight
+//! and add each one to the tree unless it would create a cycle.
+//! The cycle check is a connected query on the weighted quick union of the vertices.
+//!
+//! ```rust
+//!     # use union_finding::kruskal::{EdgeWeightedGraph, kruskal_mst};
+//!     let mut g = EdgeWeightedGraph::new(4);
+//!     g.add_edge(0, 1, 1.0);
+//!     g.add_edge(1, 2, 2.0);
+//!     g.add_edge(0, 2, 0.5);
+//!     g.add_edge(2, 3, 3.0);
+//!     let mst = kruskal_mst(&g).unwrap();
+//!     assert_eq!(3, mst.edges.len());
+//!     assert_eq!(4.5, mst.weight);
+//! ```
+//!
+//! When the graph is not connected there is no spanning tree, but `kruskal_msf` still returns
+//! a minimum spanning forest, a minimum spanning tree for each connected component.
+
+use crate::weighting::UT;
+
+/// An undirected weighted edge between v and w
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edge {
+    pub v: usize,
+    pub w: usize,
+    pub weight: f64
+}
+
+/// Undirected graph with weighted edges, vertices are 0..vertices
+pub struct EdgeWeightedGraph {
+    vertices: usize,
+    edges: Vec<Edge>
+}
+
+impl EdgeWeightedGraph {
+
+    /// Return a graph with the given number of vertices and no edges
+    pub fn new(vertices: usize) -> EdgeWeightedGraph {
+        EdgeWeightedGraph {
+            vertices,
+            edges: Vec::new()
+        }
+    }
+
+    /// Add the edge v-w. Panics if one of the vertices does not exist
+    pub fn add_edge(&mut self, v: usize, w: usize, weight: f64) {
+        assert!(v < self.vertices && w < self.vertices, "edge {}-{} out of {} vertices", v, w, self.vertices);
+        self.edges.push(Edge { v, w, weight });
+    }
+
+    pub fn vertices(&self) -> usize {
+        self.vertices
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+}
+
+/// Edges chosen by Kruskal's algorithm and their total weight
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanningForest {
+    pub edges: Vec<Edge>,
+    pub weight: f64,
+    /// Number of trees, one per connected component of the graph
+    pub trees: usize
+}
+
+/// Return a minimum spanning forest of the graph
+pub fn kruskal_msf(graph: &EdgeWeightedGraph) -> SpanningForest {
+    let mut edges = graph.edges.clone();
+    edges.sort_by(|a, b| a.weight.total_cmp(&b.weight));
+
+    let mut uf = UT::new(graph.vertices);
+    let mut forest = Vec::with_capacity(graph.vertices.saturating_sub(1));
+    let mut weight = 0.0;
+
+    for edge in edges {
+        // a spanning tree is complete, no edge can be added without a cycle
+        if uf.count() == 1 {break}
+
+        if !uf.connected(edge.v, edge.w) {
+            uf.union(edge.v, edge.w);
+            weight += edge.weight;
+            forest.push(edge);
+        }
+    }
+
+    SpanningForest {
+        edges: forest,
+        weight,
+        trees: uf.count()
+    }
+}
+
+/// Return a minimum spanning tree of the graph, None if the graph is not connected
+pub fn kruskal_mst(graph: &EdgeWeightedGraph) -> Option<SpanningForest> {
+    let forest = kruskal_msf(graph);
+    if forest.trees <= 1 { Some(forest) } else { None }
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    // tinyEWG.txt from the course
+    fn tiny() -> EdgeWeightedGraph {
+        let mut g = EdgeWeightedGraph::new(8);
+        let edges = [
+            (4, 5, 0.35), (4, 7, 0.37), (5, 7, 0.28), (0, 7, 0.16),
+            (1, 5, 0.32), (0, 4, 0.38), (2, 3, 0.17), (1, 7, 0.19),
+            (0, 2, 0.26), (1, 2, 0.36), (1, 3, 0.29), (2, 7, 0.34),
+            (6, 2, 0.40), (3, 6, 0.52), (6, 0, 0.58), (6, 4, 0.93)
+        ];
+        for &(v, w, weight) in edges.iter() {
+            g.add_edge(v, w, weight);
+        }
+        g
+    }
+
+    #[test]
+    pub fn tiny_ewg(){
+        let mst = kruskal_mst(&tiny()).unwrap();
+
+        let chosen: Vec<(usize, usize)> = mst.edges.iter().map(|e| (e.v, e.w)).collect();
+        assert_eq!(vec![(0, 7), (2, 3), (1, 7), (0, 2), (5, 7), (4, 5), (6, 2)], chosen);
+        assert!((mst.weight - 1.81).abs() < 1e-9);
+        assert_eq!(1, mst.trees);
+    }
+
+    #[test]
+    pub fn disconnected(){
+        let mut g = EdgeWeightedGraph::new(5);
+        g.add_edge(0, 1, 2.0);
+        g.add_edge(1, 2, 1.0);
+        g.add_edge(0, 2, 5.0);
+        g.add_edge(3, 4, 7.0);
+
+        assert_eq!(None, kruskal_mst(&g));
+
+        let msf = kruskal_msf(&g);
+        assert_eq!(3, msf.edges.len());
+        assert_eq!(10.0, msf.weight);
+        assert_eq!(2, msf.trees);
+    }
+
+    #[test]
+    pub fn parallel_edges_and_self_loops(){
+        let mut g = EdgeWeightedGraph::new(2);
+        g.add_edge(0, 0, -1.0);
+        g.add_edge(0, 1, 3.0);
+        g.add_edge(1, 0, 2.0);
+
+        let mst = kruskal_mst(&g).unwrap();
+        assert_eq!(vec![Edge { v: 1, w: 0, weight: 2.0 }], mst.edges);
+    }
+
+    #[test]
+    pub fn no_vertices(){
+        let mst = kruskal_mst(&EdgeWeightedGraph::new(0)).unwrap();
+        assert!(mst.edges.is_empty());
+        assert_eq!(0, mst.trees);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn missing_vertex(){
+        let mut g = EdgeWeightedGraph::new(2);
+        g.add_edge(0, 2, 1.0);
+    }
+
+}
@@ -0,0 +1,172 @@
+//!
+//! Union-find over arbitrary labels instead of the dense integers 0..n.
+//! Each label gets an index the first time it is seen, and the components are kept
+//! by a weighted quick union growing along with the labels.
+//!
+//! ```rust
+//!     # use union_finding::labeled::LabeledUnionFind;
+//!     let mut uf = LabeledUnionFind::new();
+//!     uf.union("rome", "milan");
+//!     uf.union("paris", "lyon");
+//!     assert!(uf.connected(&"milan", &"rome"));
+//!     assert!(!uf.connected(&"rome", &"paris"));
+//!     assert_eq!(2, uf.count());
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::weighting::UT;
+
+pub struct LabeledUnionFind<K> {
+    index: HashMap<K, usize>,
+    labels: Vec<K>,
+    uf: UT
+}
+
+impl<K: Hash + Eq + Clone> LabeledUnionFind<K> {
+
+    /// Return an empty instance
+    pub fn new() -> LabeledUnionFind<K> {
+        LabeledUnionFind {
+            index: HashMap::new(),
+            labels: Vec::new(),
+            uf: UT::new(0)
+        }
+    }
+
+    /// Return the index of the label, adding it in its own component if it was never seen
+    pub fn insert(&mut self, label: K) -> usize {
+        if let Some(&i) = self.index.get(&label) {
+            return i;
+        }
+
+        let i = self.uf.add_site();
+        self.index.insert(label.clone(), i);
+        self.labels.push(label);
+        i
+    }
+
+    /// Check if the label has been seen
+    pub fn contains(&self, label: &K) -> bool {
+        self.index.contains_key(label)
+    }
+
+    /// Return the number of labels
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Check if no label has been seen
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Return the number of components
+    pub fn count(&self) -> usize {
+        self.uf.count()
+    }
+
+    /// Connect two labels, adding them if they were never seen
+    pub fn union(&mut self, p: K, q: K) {
+        let pi = self.insert(p);
+        let qi = self.insert(q);
+        self.uf.union(pi, qi);
+    }
+
+    /// Check if two labels are connected. A label never seen is connected only to itself
+    pub fn connected(&mut self, p: &K, q: &K) -> bool {
+        match (self.index.get(p), self.index.get(q)) {
+            (Some(&pi), Some(&qi)) => self.uf.connected(pi, qi),
+            _ => p == q
+        }
+    }
+
+    /// Return the label representing the component of the given one, if it has been seen
+    pub fn find(&mut self, label: &K) -> Option<&K> {
+        let i = *self.index.get(label)?;
+        let r = self.uf.root(i);
+        Some(&self.labels[r])
+    }
+
+    /// Return the labels in the component of the given one, if it has been seen
+    pub fn component_of(&mut self, label: &K) -> Option<Vec<&K>> {
+        let i = *self.index.get(label)?;
+        let labels = &self.labels;
+        Some(self.uf.component_of(i).into_iter().map(|m| &labels[m]).collect())
+    }
+
+    /// Return every component as the list of its labels, in insertion order
+    pub fn components(&mut self) -> Vec<Vec<&K>> {
+        let labels = &self.labels;
+        self.uf.components()
+            .into_iter()
+            .map(|c| c.into_iter().map(|m| &labels[m]).collect())
+            .collect()
+    }
+
+}
+
+impl<K: Hash + Eq + Clone> Default for LabeledUnionFind<K> {
+    fn default() -> Self {
+        LabeledUnionFind::new()
+    }
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    #[test]
+    pub fn insert(){
+        let mut uf = LabeledUnionFind::new();
+
+        assert!(uf.is_empty());
+        assert_eq!(0, uf.insert("a"));
+        assert_eq!(1, uf.insert("b"));
+        assert_eq!(0, uf.insert("a"));
+        assert_eq!(2, uf.len());
+        assert_eq!(2, uf.count());
+    }
+
+    #[test]
+    pub fn unknown_labels(){
+        let mut uf = LabeledUnionFind::new();
+        uf.union("a", "b");
+
+        assert!(!uf.contains(&"c"));
+        assert!(uf.connected(&"c", &"c"));
+        assert!(!uf.connected(&"a", &"c"));
+        assert_eq!(None, uf.find(&"c"));
+        assert_eq!(None, uf.component_of(&"c"));
+    }
+
+    #[test]
+    pub fn tuple_labels(){
+        let mut uf = LabeledUnionFind::new();
+        uf.union((0, 0), (0, 1));
+        uf.union((0, 1), (1, 1));
+        uf.union((5, 5), (6, 5));
+
+        assert!(uf.connected(&(0, 0), &(1, 1)));
+        assert_eq!(uf.find(&(0, 0)).cloned(), uf.find(&(1, 1)).cloned());
+        assert_eq!(vec![&(0, 0), &(0, 1), &(1, 1)], uf.component_of(&(0, 1)).unwrap());
+    }
+
+    #[test]
+    pub fn string_components(){
+        let mut uf = LabeledUnionFind::new();
+        for (p, q) in [("rome", "milan"), ("paris", "lyon"), ("turin", "milan")].iter() {
+            uf.union(p.to_string(), q.to_string());
+        }
+        uf.insert("berlin".to_string());
+
+        let components: Vec<Vec<&str>> = uf.components()
+            .into_iter()
+            .map(|c| c.into_iter().map(|l| l.as_str()).collect())
+            .collect();
+        assert_eq!(vec![vec!["rome", "milan", "turin"], vec!["paris", "lyon"], vec!["berlin"]], components);
+    }
+
+}
@@ -1,7 +1,7 @@
 //! It solves the so-called [`dynamic connectivity problem`]. The lib provides three algorithms:
 //! - Quick Find
 //! - Quick Union
-//! - TODO: Weighting
+//! - Weighted Quick Union
 //!
 //! [`dynamic connectivity problem`]: https://en.wikipedia.org/wiki/Dynamic_connectivity
 //!
@@ -21,4 +21,199 @@ pub mod quickfind;
 
 pub mod quickunion;
 
-pub mod weighting;
\ No newline at end of file
+pub mod weighting;
+
+pub mod site;
+
+pub mod encoding;
+
+#[cfg(feature = "instrument")]
+pub mod instrument;
+
+mod counter;
+
+pub mod percolation;
+
+pub mod labeled;
+
+pub mod rollback;
+
+pub mod potential;
+
+pub mod concurrent;
+
+pub mod kruskal;
+
+pub mod grid;
+
+pub mod successor;
+
+pub mod timeline;
+
+pub mod maze;
+
+pub mod unify;
+
+pub mod with_data;
+
+use site::InvalidSite;
+
+/// Common interface of the union-find algorithms, so that clients can be written once
+/// and the algorithm swapped behind a generic parameter.
+///
+/// ```rust
+///     # use union_finding::UnionFind;
+///     # use union_finding::{quickfind, quickunion, weighting};
+///     fn components<U: UnionFind>(n: usize, pairs: &[(usize, usize)]) -> usize {
+///         let mut uf = U::new(n);
+///         for &(p, q) in pairs {
+///             uf.union(p, q);
+///         }
+///         uf.count()
+///     }
+///
+///     let pairs = [(4, 3), (3, 8), (6, 5), (9, 4), (2, 1)];
+///     assert_eq!(5, components::<quickfind::UT>(10, &pairs));
+///     assert_eq!(5, components::<quickunion::UT>(10, &pairs));
+///     assert_eq!(5, components::<weighting::UT>(10, &pairs));
+/// ```
+pub trait UnionFind {
+    /// Return a new instance with n objects, each one in its own component
+    fn new(n: usize) -> Self;
+
+    /// Merge the components containing p and q
+    fn union(&mut self, p: usize, q: usize);
+
+    /// Return the identifier of the component containing p
+    fn find(&mut self, p: usize) -> usize;
+
+    /// Check if p and q are in the same component
+    fn connected(&mut self, p: usize, q: usize) -> bool {
+        self.find(p) == self.find(q)
+    }
+
+    /// Return the number of components
+    fn count(&self) -> usize;
+
+    /// Return the number of sites
+    fn len(&self) -> usize;
+
+    /// Check if there are no sites
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return an error if p is not one of the sites
+    fn check(&self, p: usize) -> Result<(), InvalidSite> {
+        if p < self.len() { Ok(()) } else { Err(InvalidSite { site: p, len: self.len() }) }
+    }
+
+    /// Same as `union`, but returns an error instead of panicking on invalid sites
+    fn try_union(&mut self, p: usize, q: usize) -> Result<(), InvalidSite> {
+        self.check(p)?;
+        self.check(q)?;
+        self.union(p, q);
+        Ok(())
+    }
+
+    /// Same as `connected`, but returns an error instead of panicking on invalid sites
+    fn try_connected(&mut self, p: usize, q: usize) -> Result<bool, InvalidSite> {
+        self.check(p)?;
+        self.check(q)?;
+        Ok(self.connected(p, q))
+    }
+
+    /// Same as `find`, but returns an error instead of panicking on invalid sites
+    fn try_find(&mut self, p: usize) -> Result<usize, InvalidSite> {
+        self.check(p)?;
+        Ok(self.find(p))
+    }
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    // behaviour shared by every UnionFind, run on each type below
+
+    fn count_components<U: UnionFind>(){
+        let mut ut = U::new(10);
+        assert_eq!(10, ut.count());
+
+        ut.union(4, 3);
+        ut.union(3, 8);
+        ut.union(8, 4);
+        assert_eq!(8, ut.count());
+    }
+
+    fn union_find_trait<U: UnionFind>(){
+        let mut ut = U::new(4);
+        ut.union(0, 1);
+
+        assert!(ut.connected(0, 1));
+        assert!(!ut.connected(1, 2));
+        assert_eq!(ut.find(0), ut.find(1));
+        assert_eq!(3, ut.count());
+        assert_eq!(4, ut.len());
+    }
+
+    fn invalid_sites<U: UnionFind>(){
+        let mut ut = U::new(3);
+
+        assert_eq!(Ok(()), ut.try_union(0, 2));
+        assert_eq!(Ok(true), ut.try_connected(2, 0));
+        assert_eq!(Err(InvalidSite { site: 3, len: 3 }), ut.try_union(0, 3));
+        assert_eq!(Err(InvalidSite { site: 7, len: 3 }), ut.try_connected(7, 0));
+        assert_eq!(2, ut.count());
+    }
+
+    /// U stores its sites as u8
+    fn small_index<U: UnionFind>(){
+        let mut ut = U::new(255);
+        ut.union(0, 254);
+        ut.union(254, 100);
+
+        assert!(ut.connected(0, 100));
+        assert_eq!(253, ut.count());
+    }
+
+    macro_rules! union_find_tests {
+        ($($ut:ident),*) => {
+            $(
+                mod $ut {
+
+                    #[test]
+                    pub fn count_components(){
+                        super::count_components::<crate::$ut::UT>();
+                    }
+
+                    #[test]
+                    pub fn union_find_trait(){
+                        super::union_find_trait::<crate::$ut::UT>();
+                    }
+
+                    #[test]
+                    pub fn invalid_sites(){
+                        super::invalid_sites::<crate::$ut::UT>();
+                    }
+
+                    #[test]
+                    pub fn small_index(){
+                        super::small_index::<crate::$ut::UT<u8>>();
+                    }
+
+                    #[test]
+                    #[should_panic]
+                    pub fn index_overflow(){
+                        <crate::$ut::UT<u8> as crate::UnionFind>::new(256);
+                    }
+
+                }
+            )*
+        };
+    }
+
+    union_find_tests!(quickfind, quickunion, weighting);
+
+}
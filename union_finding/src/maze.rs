@@ -0,0 +1,225 @@
+//!
+//! Random mazes with the randomized Kruskal's algorithm: start with a wall between every pair of
+//! neighbouring cells, visit the walls in random order and remove a wall when the two cells it
+//! separates are not connected yet. The result is a perfect maze, exactly one path between any two cells.
+//!
+//! The walls are shuffled with the Knuth shuffle of `elementary_sort`, and a seed makes the maze reproducible.
+//!
+//! ```rust
+//!     # use union_finding::maze::Maze;
+//!     let maze = Maze::generate(3, 2, 42);
+//!     assert_eq!(maze.to_ascii(), Maze::generate(3, 2, 42).to_ascii());
+//! ```
+
+use elementary_sort::knuth_shuffle::knuth_shuffling_with;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::weighting::UT;
+
+/// A wall between the cell and its neighbour on the right or below
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Wall {
+    East(usize),
+    South(usize)
+}
+
+/// A width x height grid of cells, the cell (x, y) is at index y * width + x
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze {
+    width: usize,
+    height: usize,
+    // wall between the cell and the one on its right, the last column always has one
+    east: Vec<bool>,
+    // wall between the cell and the one below, the last row always has one
+    south: Vec<bool>
+}
+
+impl Maze {
+
+    /// Generate a maze, the same seed always gives the same maze
+    pub fn generate(width: usize, height: usize, seed: u64) -> Maze {
+        Maze::generate_with(width, height, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Generate a maze drawing the wall order from the given generator
+    pub fn generate_with<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Maze {
+        let cells = width * height;
+        let mut maze = Maze {
+            width,
+            height,
+            east: vec![true; cells],
+            south: vec![true; cells]
+        };
+
+        let mut walls = Vec::with_capacity(2 * cells);
+        for y in 0..height {
+            for x in 0..width {
+                let cell = y * width + x;
+                if x + 1 < width {walls.push(Wall::East(cell))}
+                if y + 1 < height {walls.push(Wall::South(cell))}
+            }
+        }
+        knuth_shuffling_with(&mut walls, rng);
+
+        let mut uf = UT::new(cells);
+        for wall in walls {
+            // every cell is reachable, the remaining walls would all close a cycle
+            if uf.count() <= 1 {break}
+
+            let (a, b) = match wall {
+                Wall::East(cell) => (cell, cell + 1),
+                Wall::South(cell) => (cell, cell + width)
+            };
+            if uf.connected(a, b) {continue}
+
+            uf.union(a, b);
+            match wall {
+                Wall::East(cell) => maze.east[cell] = false,
+                Wall::South(cell) => maze.south[cell] = false
+            }
+        }
+
+        maze
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Check if there is a wall on the right of the cell (x, y). The border is a wall
+    pub fn wall_east(&self, x: usize, y: usize) -> bool {
+        self.east[self.cell(x, y)]
+    }
+
+    /// Check if there is a wall below the cell (x, y). The border is a wall
+    pub fn wall_south(&self, x: usize, y: usize) -> bool {
+        self.south[self.cell(x, y)]
+    }
+
+    fn cell(&self, x: usize, y: usize) -> usize {
+        assert!(x < self.width && y < self.height, "cell ({}, {}) is outside the {}x{} maze", x, y, self.width, self.height);
+        y * self.width + x
+    }
+
+    /// Render the maze with `+`, `-` and `|`, one line per row of walls or cells
+    pub fn to_ascii(&self) -> String {
+        let mut out = String::new();
+
+        out.push('+');
+        for _ in 0..self.width {
+            out.push_str("---+");
+        }
+        out.push('\n');
+
+        for y in 0..self.height {
+            out.push('|');
+            for x in 0..self.width {
+                out.push_str("   ");
+                out.push(if self.wall_east(x, y) { '|' } else { ' ' });
+            }
+            out.push('\n');
+
+            out.push('+');
+            for x in 0..self.width {
+                out.push_str(if self.wall_south(x, y) { "---" } else { "   " });
+                out.push('+');
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Return a (2 * height + 1) x (2 * width + 1) bitmap, true for walls.
+    /// The cell (x, y) is the pixel (2x + 1, 2y + 1), the pixels between two cells tell if a wall separates them
+    pub fn to_bitmap(&self) -> Vec<Vec<bool>> {
+        let mut bitmap = vec![vec![true; 2 * self.width + 1]; 2 * self.height + 1];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                bitmap[2 * y + 1][2 * x + 1] = false;
+                bitmap[2 * y + 1][2 * x + 2] = self.wall_east(x, y);
+                bitmap[2 * y + 2][2 * x + 1] = self.wall_south(x, y);
+            }
+        }
+
+        bitmap
+    }
+
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    fn removed_walls(maze: &Maze) -> usize {
+        maze.east.iter().chain(maze.south.iter()).filter(|&&w| !w).count()
+    }
+
+    // count the open pixels reachable from the first cell
+    fn reachable(bitmap: &[Vec<bool>]) -> usize {
+        let mut seen = vec![vec![false; bitmap[0].len()]; bitmap.len()];
+        let mut stack = vec![(1, 1)];
+        let mut cells = 0;
+        seen[1][1] = true;
+
+        while let Some((r, c)) = stack.pop() {
+            if r % 2 == 1 && c % 2 == 1 {cells += 1}
+            for &(nr, nc) in [(r - 1, c), (r + 1, c), (r, c - 1), (r, c + 1)].iter() {
+                if !bitmap[nr][nc] && !seen[nr][nc] {
+                    seen[nr][nc] = true;
+                    stack.push((nr, nc));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    pub fn perfect_maze(){
+        let maze = Maze::generate(12, 7, 1);
+
+        // a spanning tree of the cells
+        assert_eq!(12 * 7 - 1, removed_walls(&maze));
+        assert_eq!(12 * 7, reachable(&maze.to_bitmap()));
+    }
+
+    #[test]
+    pub fn reproducible(){
+        assert_eq!(Maze::generate(10, 10, 7), Maze::generate(10, 10, 7));
+        assert_ne!(Maze::generate(10, 10, 7), Maze::generate(10, 10, 8));
+    }
+
+    #[test]
+    pub fn borders(){
+        let maze = Maze::generate(4, 3, 3);
+        for y in 0..3{
+            assert!(maze.wall_east(3, y));
+        }
+        for x in 0..4{
+            assert!(maze.wall_south(x, 2));
+        }
+
+        let bitmap = maze.to_bitmap();
+        assert_eq!(7, bitmap.len());
+        assert_eq!(9, bitmap[0].len());
+        assert!(bitmap[0].iter().all(|&w| w));
+        assert!(bitmap.iter().all(|row| row[0] && row[8]));
+    }
+
+    #[test]
+    pub fn ascii(){
+        assert_eq!("+---+\n|   |\n+---+\n", Maze::generate(1, 1, 0).to_ascii());
+
+        // two cells side by side, the wall between them must go
+        assert_eq!("+---+---+\n|       |\n+---+---+\n", Maze::generate(2, 1, 0).to_ascii());
+    }
+
+}
@@ -0,0 +1,281 @@
+//!
+//! Percolation is the classic application of union-find: given an n-by-n grid of sites,
+//! each one either open or blocked, the system percolates if there is a path of open sites
+//! connecting the top row to the bottom row.
+//!
+//! A site is full if it can be reached from the top row through open sites.
+//!
+//! ```rust
+//!     # use union_finding::percolation::Percolation;
+//!     let mut p = Percolation::new(3);
+//!     p.open(0, 1);
+//!     p.open(1, 1);
+//!     assert!(!p.percolates());
+//!     p.open(2, 1);
+//!     assert!(p.percolates());
+//! ```
+//!
+//! Running a lot of random experiments gives an estimation of the percolation threshold,
+//! the fraction of open sites needed for the system to percolate (about 0.593).
+//!
+//! ```rust
+//!     # use union_finding::percolation::PercolationStats;
+//!     let stats = PercolationStats::new(10, 30);
+//!     assert!(stats.confidence_lo() <= stats.mean() && stats.mean() <= stats.confidence_hi());
+//! ```
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::weighting::UT;
+
+/// An n-by-n grid of sites. Rows and columns are zero-based.
+pub struct Percolation {
+    n: usize,
+    open: Vec<bool>,
+    open_count: usize,
+    // grid sites plus a virtual top (n*n) and a virtual bottom (n*n + 1)
+    percolation: UT,
+    // grid sites plus the virtual top only.
+    // Checking fullness on `percolation` would suffer from backwash: once the system percolates
+    // every open site connected to the bottom would look full through the virtual bottom.
+    fullness: UT
+}
+
+impl Percolation {
+
+    /// Return a new n-by-n grid with all sites blocked
+    pub fn new(n: usize) -> Percolation {
+        assert!(n > 0, "the grid must have at least one site");
+
+        let sites = n * n;
+
+        Percolation {
+            n,
+            open: vec![false; sites],
+            open_count: 0,
+            percolation: UT::new(sites + 2),
+            fullness: UT::new(sites + 1)
+        }
+    }
+
+    fn top(&self) -> usize {
+        self.n * self.n
+    }
+
+    fn bottom(&self) -> usize {
+        self.n * self.n + 1
+    }
+
+    fn site(&self, row: usize, col: usize) -> usize {
+        assert!(row < self.n && col < self.n, "site ({}, {}) is outside the {}-by-{} grid", row, col, self.n, self.n);
+        row * self.n + col
+    }
+
+    /// Open the site (row, col) if it is not open already
+    pub fn open(&mut self, row: usize, col: usize) {
+        let site = self.site(row, col);
+        if self.open[site] {return}
+
+        self.open[site] = true;
+        self.open_count += 1;
+
+        if row == 0 {
+            let top = self.top();
+            self.percolation.union(site, top);
+            self.fullness.union(site, top);
+        }
+        if row == self.n - 1 {
+            let bottom = self.bottom();
+            self.percolation.union(site, bottom);
+        }
+
+        let mut neighbours = Vec::with_capacity(4);
+        if row > 0 {neighbours.push(site - self.n)}
+        if row < self.n - 1 {neighbours.push(site + self.n)}
+        if col > 0 {neighbours.push(site - 1)}
+        if col < self.n - 1 {neighbours.push(site + 1)}
+
+        for neighbour in neighbours {
+            if self.open[neighbour] {
+                self.percolation.union(site, neighbour);
+                self.fullness.union(site, neighbour);
+            }
+        }
+    }
+
+    /// Check if the site (row, col) is open
+    pub fn is_open(&self, row: usize, col: usize) -> bool {
+        self.open[self.site(row, col)]
+    }
+
+    /// Check if the site (row, col) is connected to the top row through open sites
+    pub fn is_full(&mut self, row: usize, col: usize) -> bool {
+        let site = self.site(row, col);
+        let top = self.top();
+        self.open[site] && self.fullness.connected(site, top)
+    }
+
+    /// Return the number of open sites
+    pub fn number_of_open_sites(&self) -> usize {
+        self.open_count
+    }
+
+    /// Check if the system percolates
+    pub fn percolates(&mut self) -> bool {
+        let top = self.top();
+        let bottom = self.bottom();
+        self.percolation.connected(top, bottom)
+    }
+
+}
+
+/// Monte Carlo estimation of the percolation threshold
+pub struct PercolationStats {
+    thresholds: Vec<f64>
+}
+
+impl PercolationStats {
+
+    /// Run `trials` independent experiments on an n-by-n grid
+    pub fn new(n: usize, trials: usize) -> PercolationStats {
+        PercolationStats::with_rng(n, trials, &mut rand::thread_rng())
+    }
+
+    /// Same as `new` but draws the random sites from the given generator, so the experiments can be reproduced
+    pub fn with_rng<R: Rng>(n: usize, trials: usize, rng: &mut R) -> PercolationStats {
+        assert!(n > 0, "the grid must have at least one site");
+        assert!(trials > 0, "at least one trial is required");
+
+        let mut sites: Vec<usize> = (0..n * n).collect();
+        let mut thresholds = Vec::with_capacity(trials);
+
+        for _ in 0..trials {
+            // opening the sites in a random order picks each time a random blocked site
+            sites.shuffle(rng);
+
+            let mut percolation = Percolation::new(n);
+            for &site in &sites {
+                percolation.open(site / n, site % n);
+                if percolation.percolates() {break}
+            }
+
+            thresholds.push(percolation.number_of_open_sites() as f64 / (n * n) as f64);
+        }
+
+        PercolationStats {
+            thresholds
+        }
+    }
+
+    /// Sample mean of the percolation threshold
+    pub fn mean(&self) -> f64 {
+        self.thresholds.iter().sum::<f64>() / self.thresholds.len() as f64
+    }
+
+    /// Sample standard deviation of the percolation threshold, NaN with a single trial
+    pub fn stddev(&self) -> f64 {
+        let mean = self.mean();
+        let squares: f64 = self.thresholds.iter().map(|t| (t - mean) * (t - mean)).sum();
+        (squares / (self.thresholds.len() as f64 - 1.0)).sqrt()
+    }
+
+    fn half_width(&self) -> f64 {
+        1.96 * self.stddev() / (self.thresholds.len() as f64).sqrt()
+    }
+
+    /// Low endpoint of the 95% confidence interval
+    pub fn confidence_lo(&self) -> f64 {
+        self.mean() - self.half_width()
+    }
+
+    /// High endpoint of the 95% confidence interval
+    pub fn confidence_hi(&self) -> f64 {
+        self.mean() + self.half_width()
+    }
+
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    pub fn blocked_grid(){
+        let mut p = Percolation::new(3);
+
+        assert_eq!(0, p.number_of_open_sites());
+        assert!(!p.is_open(1, 1));
+        assert!(!p.is_full(1, 1));
+        assert!(!p.percolates());
+    }
+
+    #[test]
+    pub fn single_site(){
+        let mut p = Percolation::new(1);
+        assert!(!p.percolates());
+
+        p.open(0, 0);
+        assert!(p.is_full(0, 0));
+        assert!(p.percolates());
+    }
+
+    #[test]
+    pub fn open_twice(){
+        let mut p = Percolation::new(3);
+        p.open(1, 1);
+        p.open(1, 1);
+
+        assert!(p.is_open(1, 1));
+        assert_eq!(1, p.number_of_open_sites());
+    }
+
+    #[test]
+    pub fn percolates_through_a_path(){
+        let mut p = Percolation::new(4);
+        p.open(0, 0);
+        p.open(1, 0);
+        p.open(1, 1);
+        p.open(2, 1);
+        assert!(p.is_full(2, 1));
+        assert!(!p.percolates());
+
+        p.open(3, 1);
+        assert!(p.percolates());
+    }
+
+    #[test]
+    pub fn no_backwash(){
+        let mut p = Percolation::new(3);
+        p.open(0, 0);
+        p.open(1, 0);
+        p.open(2, 0);
+        // connected to the bottom but not to the top
+        p.open(2, 2);
+
+        assert!(p.percolates());
+        assert!(!p.is_full(2, 2));
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn out_of_grid(){
+        let mut p = Percolation::new(3);
+        p.open(3, 0);
+    }
+
+    #[test]
+    pub fn threshold_estimation(){
+        let mut rng = StdRng::seed_from_u64(42);
+        let stats = PercolationStats::with_rng(20, 100, &mut rng);
+
+        assert!(stats.mean() > 0.55 && stats.mean() < 0.65);
+        assert!(stats.stddev() > 0.0);
+        assert!(stats.confidence_lo() < stats.mean());
+        assert!(stats.confidence_hi() > stats.mean());
+    }
+
+}
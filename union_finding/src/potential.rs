@@ -0,0 +1,241 @@
+//!
+//! Weighted quick union where every site carries a value known only relatively to the others.
+//! Along with "p and q are connected" it records "value(p) - value(q) = d", so it can answer
+//! the offset between two connected sites and detect inconsistent measurements.
+//!
+//! ```rust
+//!     # use union_finding::potential::UT;
+//!     let mut ut = UT::<i64>::new(3);
+//!     ut.union(0, 1, 5).unwrap();
+//!     ut.union(1, 2, 3).unwrap();
+//!     assert_eq!(Some(8), ut.diff(0, 2));
+//!     assert!(ut.union(2, 0, 1).is_err());
+//! ```
+//!
+//! With `bool` the offset is a parity, which checks bipartiteness: an edge p-q means p and q have opposite colors.
+//!
+//! ```rust
+//!     # use union_finding::potential::UT;
+//!     let mut ut = UT::<bool>::new(3);
+//!     ut.union(0, 1, true).unwrap();
+//!     ut.union(1, 2, true).unwrap();
+//!     // odd cycle: the graph is not bipartite
+//!     assert!(ut.union(2, 0, true).is_err());
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use crate::site;
+
+/// The group of the offsets between sites
+pub trait Potential: Copy + PartialEq {
+    /// Offset between a site and itself
+    fn zero() -> Self;
+    /// Compose two offsets: (a - b) and (b - c) give (a - c)
+    fn add(self, other: Self) -> Self;
+    /// Reverse an offset: (a - b) gives (b - a)
+    fn neg(self) -> Self;
+}
+
+macro_rules! impl_potential {
+    ($($t:ty),*) => {
+        $(
+            impl Potential for $t {
+                fn zero() -> Self { 0 }
+                fn add(self, other: Self) -> Self { self + other }
+                fn neg(self) -> Self { -self }
+            }
+        )*
+    };
+}
+
+impl_potential!(i8, i16, i32, i64, i128, isize);
+
+/// Parity: true when the two sites are on opposite sides
+impl Potential for bool {
+    fn zero() -> Self { false }
+    fn add(self, other: Self) -> Self { self ^ other }
+    fn neg(self) -> Self { self }
+}
+
+/// Returned by `union` when the new relation disagrees with the known one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contradiction<D> {
+    /// value(p) - value(q) as already recorded
+    pub known: D,
+    /// value(p) - value(q) as requested by the union
+    pub requested: D
+}
+
+impl<D: fmt::Debug> fmt::Display for Contradiction<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "offset is already {:?}, cannot be {:?}", self.known, self.requested)
+    }
+}
+
+impl<D: fmt::Debug> Error for Contradiction<D> {}
+
+pub struct UT<D> {
+    id: Vec<u32>,
+    weights: Vec<u32>,
+    // value(i) - value(id[i])
+    offsets: Vec<D>,
+    count: usize
+}
+
+impl<D: Potential> UT<D> {
+
+    /// Return a new instance with n sites, each one in its own component
+    pub fn new(n: usize) -> UT<D> {
+        site::check_capacity::<u32>(n);
+
+        UT {
+            id: (0..n as u32).collect(),
+            weights: vec![1; n],
+            offsets: vec![D::zero(); n],
+            count: n
+        }
+    }
+
+    /// Return the root of i together with value(i) - value(root)
+    pub fn root(&mut self, mut i: usize) -> (usize, D) {
+        let mut offset = D::zero();
+
+        while self.id[i] != i as u32 {
+            // path halving, as in weighting::UT. Skipping the parent means adding its offset
+            let parent = self.id[i] as usize;
+            self.offsets[i] = self.offsets[i].add(self.offsets[parent]);
+            self.id[i] = self.id[parent];
+
+            offset = offset.add(self.offsets[i]);
+            i = self.id[i] as usize;
+        }
+
+        (i, offset)
+    }
+
+    pub fn connected(&mut self, p: usize, q: usize) -> bool {
+        self.root(p).0 == self.root(q).0
+    }
+
+    /// Return value(p) - value(q), if p and q are connected
+    pub fn diff(&mut self, p: usize, q: usize) -> Option<D> {
+        let (pi, dp) = self.root(p);
+        let (qi, dq) = self.root(q);
+
+        if pi == qi { Some(dp.add(dq.neg())) } else { None }
+    }
+
+    /// Record value(p) - value(q) = d, connecting p and q.
+    /// If they are already connected with a different offset nothing changes and the error is returned
+    pub fn union(&mut self, p: usize, q: usize, d: D) -> Result<(), Contradiction<D>> {
+        let (pi, dp) = self.root(p);
+        let (qi, dq) = self.root(q);
+
+        if pi == qi {
+            let known = dp.add(dq.neg());
+            return if known == d { Ok(()) } else { Err(Contradiction { known, requested: d }) };
+        }
+
+        // value(pi) - value(qi) = d - dp + dq
+        let roots = d.add(dp.neg()).add(dq);
+
+        if self.weights[pi] > self.weights[qi] {
+            self.id[qi] = pi as u32;
+            self.offsets[qi] = roots.neg();
+            self.weights[pi] += self.weights[qi];
+        } else {
+            self.id[pi] = qi as u32;
+            self.offsets[pi] = roots;
+            self.weights[qi] += self.weights[pi];
+        }
+        self.count -= 1;
+
+        Ok(())
+    }
+
+    /// Return the number of components
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    #[test]
+    pub fn simple_init(){
+        let mut ut = UT::<i32>::new(3);
+        assert_eq!(ut.id, [0,1,2]);
+        assert_eq!(Some(0), ut.diff(1, 1));
+        assert_eq!(None, ut.diff(0, 1));
+    }
+
+    #[test]
+    pub fn offsets(){
+        let mut ut = UT::<i64>::new(6);
+        ut.union(0, 1, 3).unwrap();
+        ut.union(2, 3, -4).unwrap();
+        ut.union(4, 5, 10).unwrap();
+        ut.union(1, 3, 1).unwrap();
+        ut.union(5, 2, 2).unwrap();
+
+        // value(i) is i-th entry
+        let values = [8, 5, 0, 4, 12, 2];
+        for p in 0..6{
+            for q in 0..6{
+                assert_eq!(Some(values[p] - values[q]), ut.diff(p, q));
+            }
+        }
+        assert_eq!(1, ut.count());
+    }
+
+    #[test]
+    pub fn contradiction(){
+        let mut ut = UT::<i32>::new(3);
+        ut.union(0, 1, 2).unwrap();
+        ut.union(1, 2, 2).unwrap();
+
+        assert_eq!(Ok(()), ut.union(0, 2, 4));
+        assert_eq!(Err(Contradiction { known: -4, requested: 1 }), ut.union(2, 0, 1));
+        assert_eq!(Some(4), ut.diff(0, 2));
+        assert_eq!(1, ut.count());
+    }
+
+    #[test]
+    pub fn bipartite(){
+        // even cycle 0-1-2-3-0 plus the edge 4-5
+        let mut ut = UT::<bool>::new(6);
+        for &(p, q) in [(0, 1), (1, 2), (2, 3), (3, 0), (4, 5)].iter(){
+            assert!(ut.union(p, q, true).is_ok());
+        }
+        assert_eq!(Some(false), ut.diff(0, 2));
+        assert_eq!(Some(true), ut.diff(1, 2));
+        assert_eq!(None, ut.diff(0, 4));
+
+        // closing an odd cycle
+        assert!(ut.union(0, 2, true).is_err());
+    }
+
+    #[test]
+    pub fn long_chain(){
+        let n = 1000;
+        let mut ut = UT::<i64>::new(n);
+        for i in 1..n{
+            ut.union(i, i - 1, 1).unwrap();
+        }
+        assert_eq!(Some(999), ut.diff(999, 0));
+        assert_eq!(Some(-500), ut.diff(250, 750));
+    }
+
+    #[test]
+    pub fn display(){
+        let err = Contradiction { known: 3, requested: 4 };
+        assert_eq!("offset is already 3, cannot be 4", err.to_string());
+    }
+
+}
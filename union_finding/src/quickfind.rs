@@ -17,31 +17,75 @@
 //!     assert_eq!(true, ut.connected(0, 1));
 //! ```
 
-/// The basic struct
-pub struct UT {
-    id: Vec<u32>
+use crate::UnionFind;
+use crate::site::{self, SiteIndex};
+use crate::encoding::{self, DecodeError, Reader};
+use crate::counter::Counter;
+#[cfg(feature = "instrument")]
+use crate::instrument::{AccessStats, Instrumented};
+
+/// The basic struct. Ids are stored as I, u32 by default
+pub struct UT<I = u32> {
+    id: Vec<I>,
+    count: usize,
+    counter: Counter
 }
 
 impl UT{
 
     /// Return a new Quick Find data structure instance with capacity n
-    pub fn new(n: u32) -> UT{
+    pub fn new(n: usize) -> UT{
+        UT::with_sites(n)
+    }
+
+}
+
+impl<I: SiteIndex> UT<I>{
 
-        let mut r: Vec<u32> = Vec::with_capacity(n as usize);
+    /// Return a new instance with capacity n storing the ids as I.
+    /// Panics if n sites do not fit in I
+    pub fn with_sites(n: usize) -> UT<I>{
+        site::check_capacity::<I>(n);
+
+        let mut r: Vec<I> = Vec::with_capacity(n);
 
         for i in 0..n{
-            r.push(i);
+            r.push(I::from_usize(i));
         }
 
         UT{
-            id: r
+            id: r,
+            count: n,
+            counter: Counter::default()
         }
 
     }
 
+    /// Return the number of sites
+    pub fn len(&self) -> usize{
+        self.id.len()
+    }
+
+    /// Check if there are no sites
+    pub fn is_empty(&self) -> bool{
+        self.id.is_empty()
+    }
+
+    /// Return the id of the component containing p
+    pub fn find(&self, p: usize) -> usize{
+        self.counter.read(1);
+        self.id[p].to_usize()
+    }
+
     /// check if two components are connected
     pub fn connected(&self, p: usize, q: usize) -> bool{
-        &self.id[p] == &self.id[q]
+        self.counter.read(2);
+        self.id[p] == self.id[q]
+    }
+
+    /// Return the number of components
+    pub fn count(&self) -> usize{
+        self.count
     }
 
     /// connect two components
@@ -49,12 +93,93 @@ impl UT{
         // inefficient. Considering M operations on N items this requires O(NM)
         let pid = self.id[p];
         let qid = self.id[q];
+        self.counter.read(2);
+
+        if pid == qid {return}
         
-        for i in 0..self.id.len(){
-            if self.id[i] == pid {
-                self.id[i] = qid;
+        let mut changed = 0;
+        for id in self.id.iter_mut(){
+            if *id == pid {
+                *id = qid;
+                changed += 1;
+            }
+        }
+        self.counter.read(self.id.len() as u64);
+        self.counter.write(changed);
+        self.count -= 1;
+    }
+
+    /// Encode the state, see the encoding module
+    pub fn to_bytes(&self) -> Vec<u8>{
+        encoding::encode(encoding::QUICKFIND, &[], &self.id)
+    }
+
+    /// Restore a state encoded by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<UT<I>, DecodeError>{
+        let (mut reader, width) = Reader::new(bytes, encoding::QUICKFIND)?;
+        let id: Vec<I> = reader.id(width)?;
+        reader.finish()?;
+
+        // a component id is always the index of one of its members
+        for (site, label) in id.iter().enumerate(){
+            if id[label.to_usize()] != *label{
+                return Err(DecodeError::InvalidLabel { site });
             }
         }
+        let count = id.iter().enumerate().filter(|&(i, label)| label.to_usize() == i).count();
+
+        Ok(UT{
+            id,
+            count,
+            counter: Counter::default()
+        })
+    }
+
+}
+
+impl<I: SiteIndex> UnionFind for UT<I>{
+
+    fn new(n: usize) -> UT<I>{
+        UT::with_sites(n)
+    }
+
+    fn union(&mut self, p: usize, q: usize){
+        UT::union(self, p, q)
+    }
+
+    fn find(&mut self, p: usize) -> usize{
+        UT::find(self, p)
+    }
+
+    fn connected(&mut self, p: usize, q: usize) -> bool{
+        UT::connected(self, p, q)
+    }
+
+    fn count(&self) -> usize{
+        self.count
+    }
+
+    fn len(&self) -> usize{
+        self.id.len()
+    }
+
+}
+
+#[cfg(feature = "instrument")]
+impl<I: SiteIndex> Instrumented for UT<I>{
+
+    fn set_instrumented(&mut self, enabled: bool){
+        self.counter.set_enabled(enabled);
+    }
+
+    fn access_stats(&self) -> AccessStats{
+        // every site points directly to the id of its component
+        let max_height = self.id.iter().enumerate().any(|(i, id)| id.to_usize() != i) as usize;
+        self.counter.stats(max_height)
+    }
+
+    fn reset_access_stats(&mut self){
+        self.counter.reset();
     }
 
 }
@@ -62,7 +187,8 @@ impl UT{
 #[cfg(test)]
 mod test{
 
-    use super::UT;
+    use super::*;
+    use crate::encoding::DecodeError;
 
     #[test]
     pub fn simple_init(){
@@ -76,20 +202,54 @@ mod test{
     pub fn connected_shoud_fail(){
         let ut = UT::new(10);
 
-        assert_eq!(false, ut.connected(0, 1));
-        assert_eq!(false, ut.connected(0, 2));
-        assert_eq!(false, ut.connected(3, 2));
+        assert!(!ut.connected(0, 1));
+        assert!(!ut.connected(0, 2));
+        assert!(!ut.connected(3, 2));
     }
 
+    #[test]
     pub fn connected(){
         let mut ut = UT::new(4);
         ut.union(0, 1);
         ut.union(2, 3);
         
-        assert_eq!(true, ut.connected(0, 1));
-        assert_eq!(true, ut.connected(2, 3));
-        assert_eq!(false, ut.connected(0, 3));
+        assert!(ut.connected(0, 1));
+        assert!(ut.connected(2, 3));
+        assert!(!ut.connected(0, 3));
     
     }
 
+    #[test]
+    pub fn round_trip(){
+        let mut ut = UT::<u16>::with_sites(6);
+        ut.union(0, 1);
+        ut.union(4, 1);
+        let mut restored = UT::<u16>::from_bytes(&ut.to_bytes()).unwrap();
+
+        assert_eq!(ut.id, restored.id);
+        assert_eq!(4, restored.count());
+        restored.union(2, 3);
+        assert!(restored.connected(0, 4));
+        assert!(restored.connected(2, 3));
+    }
+
+    #[test]
+    pub fn corrupted_bytes(){
+        let mut ut = UT::new(4);
+        ut.union(0, 1);
+        let bytes = ut.to_bytes();
+
+        // ids are the last 4 entries of 4 bytes: make site 2 point to site 0, whose id is 1
+        let mut label = bytes.clone();
+        label[bytes.len() - 8] = 0;
+        assert_eq!(Err(DecodeError::InvalidLabel { site: 2 }), UT::<u32>::from_bytes(&label).map(|_| ()));
+
+        let mut range = bytes.clone();
+        range[bytes.len() - 4] = 9;
+        assert_eq!(Err(DecodeError::OutOfRange { site: 3, parent: 9 }), UT::<u32>::from_bytes(&range).map(|_| ()));
+
+        assert_eq!(Err(DecodeError::Truncated), UT::<u32>::from_bytes(&bytes[..bytes.len() - 1]).map(|_| ()));
+        assert_eq!(Err(DecodeError::Header), crate::quickunion::UT::<u32>::from_bytes(&bytes).map(|_| ()));
+    }
+
 }
\ No newline at end of file
@@ -15,24 +15,59 @@
 //!     ut.union(0, 1);
 //!     assert_eq!(true, ut.connected(0, 1));
 //! ```
-pub struct UT {
-    id: Vec<u32>
+
+use crate::UnionFind;
+use crate::site::{self, SiteIndex};
+use crate::encoding::{self, DecodeError, Reader};
+use crate::counter::Counter;
+#[cfg(feature = "instrument")]
+use crate::instrument::{self, AccessStats, Instrumented};
+
+/// Parents are stored as I, u32 by default
+pub struct UT<I = u32> {
+    id: Vec<I>,
+    count: usize,
+    counter: Counter
 }
 
 impl UT{
 
     /// Return a new Quick Union data structure instance with capacity n
-    pub fn new(n: u32) -> UT{
-        let mut r: Vec<u32> = Vec::with_capacity(n as usize);
+    pub fn new(n: usize) -> UT{
+        UT::with_sites(n)
+    }
+
+}
+
+impl<I: SiteIndex> UT<I>{
+
+    /// Return a new instance with capacity n storing the parents as I.
+    /// Panics if n sites do not fit in I
+    pub fn with_sites(n: usize) -> UT<I>{
+        site::check_capacity::<I>(n);
+
+        let mut r: Vec<I> = Vec::with_capacity(n);
         for i in 0..n{
-            r.push(i);
+            r.push(I::from_usize(i));
         }
 
         UT{
-            id: r
+            id: r,
+            count: n,
+            counter: Counter::default()
         }
     }
 
+    /// Return the number of sites
+    pub fn len(&self) -> usize{
+        self.id.len()
+    }
+
+    /// Check if there are no sites
+    pub fn is_empty(&self) -> bool{
+        self.id.is_empty()
+    }
+
 
     /// Navigate the tree until it find the root of the input component 
     pub fn root(&self, mut i: usize) -> usize{
@@ -40,11 +75,13 @@ impl UT{
         // root operation could involve an access to all the element of the array
 
         // we find the root when the element we're checking points to itself
-        while self.id[i as usize] != i as u32{
-            i = self.id[i] as usize;
+        while self.id[i].to_usize() != i{
+            i = self.id[i].to_usize();
+            self.counter.read(2);
         }
+        self.counter.read(1);
 
-        self.id[i as usize] as usize
+        i
     }
 
     /// Check if two components are connected looking if they're part of the same tree
@@ -56,7 +93,83 @@ impl UT{
     pub fn union(&mut self, p: usize, q: usize){
         let pi = self.root(p);
         let qi = self.root(q);
-        self.id[pi] = qi as u32;
+
+        if pi == qi {return}
+
+        self.id[pi] = I::from_usize(qi);
+        self.counter.write(1);
+        self.count -= 1;
+    }
+
+    /// Return the number of components
+    pub fn count(&self) -> usize{
+        self.count
+    }
+
+    /// Encode the state, see the encoding module
+    pub fn to_bytes(&self) -> Vec<u8>{
+        encoding::encode(encoding::QUICKUNION, &[], &self.id)
+    }
+
+    /// Restore a state encoded by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<UT<I>, DecodeError>{
+        let (mut reader, width) = Reader::new(bytes, encoding::QUICKUNION)?;
+        let id: Vec<I> = reader.id(width)?;
+        reader.finish()?;
+
+        let roots = encoding::roots(&id)?;
+        let count = roots.iter().enumerate().filter(|&(i, &r)| r == i).count();
+
+        Ok(UT{
+            id,
+            count,
+            counter: Counter::default()
+        })
+    }
+
+}
+
+impl<I: SiteIndex> UnionFind for UT<I>{
+
+    fn new(n: usize) -> UT<I>{
+        UT::with_sites(n)
+    }
+
+    fn union(&mut self, p: usize, q: usize){
+        UT::union(self, p, q)
+    }
+
+    fn find(&mut self, p: usize) -> usize{
+        self.root(p)
+    }
+
+    fn connected(&mut self, p: usize, q: usize) -> bool{
+        UT::connected(self, p, q)
+    }
+
+    fn count(&self) -> usize{
+        self.count
+    }
+
+    fn len(&self) -> usize{
+        self.id.len()
+    }
+
+}
+
+#[cfg(feature = "instrument")]
+impl<I: SiteIndex> Instrumented for UT<I>{
+
+    fn set_instrumented(&mut self, enabled: bool){
+        self.counter.set_enabled(enabled);
+    }
+
+    fn access_stats(&self) -> AccessStats{
+        self.counter.stats(instrument::max_height(&self.id, I::to_usize))
+    }
+
+    fn reset_access_stats(&mut self){
+        self.counter.reset();
     }
 
 }
@@ -65,6 +178,7 @@ impl UT{
 mod test{
 
     use super::*;
+    use crate::encoding::DecodeError;
 
     #[test]
     pub fn simple_init(){
@@ -78,20 +192,56 @@ mod test{
     pub fn connected_shoud_fail(){
         let ut = UT::new(10);
 
-        assert_eq!(false, ut.connected(0, 1));
-        assert_eq!(false, ut.connected(0, 2));
-        assert_eq!(false, ut.connected(3, 2));
+        assert!(!ut.connected(0, 1));
+        assert!(!ut.connected(0, 2));
+        assert!(!ut.connected(3, 2));
     }
 
+    #[test]
     pub fn connected(){
         let mut ut = UT::new(4);
         ut.union(0, 1);
         ut.union(2, 3);
         
-        assert_eq!(true, ut.connected(0, 1));
-        assert_eq!(true, ut.connected(2, 3));
-        assert_eq!(false, ut.connected(0, 3));
+        assert!(ut.connected(0, 1));
+        assert!(ut.connected(2, 3));
+        assert!(!ut.connected(0, 3));
     
     }
 
+    #[test]
+    pub fn round_trip(){
+        let mut ut = UT::new(6);
+        ut.union(0, 1);
+        ut.union(1, 2);
+        ut.union(4, 5);
+        let restored: UT = UT::from_bytes(&ut.to_bytes()).unwrap();
+
+        assert_eq!(ut.id, restored.id);
+        assert_eq!(3, restored.count());
+
+        // decoding into a wider index type
+        let wide = UT::<u64>::from_bytes(&ut.to_bytes()).unwrap();
+        assert!(wide.connected(0, 2));
+    }
+
+    #[test]
+    pub fn corrupted_bytes(){
+        let ut = UT::<u8>::with_sites(3);
+        let mut bytes = ut.to_bytes();
+        let n = bytes.len();
+
+        // 0 -> 1 -> 2 -> 0
+        bytes[n - 3] = 1;
+        bytes[n - 2] = 2;
+        bytes[n - 1] = 0;
+        assert_eq!(Err(DecodeError::Cycle { site: 0 }), UT::<u8>::from_bytes(&bytes).map(|_| ()));
+
+        bytes[n - 1] = 3;
+        assert_eq!(Err(DecodeError::OutOfRange { site: 2, parent: 3 }), UT::<u8>::from_bytes(&bytes).map(|_| ()));
+
+        bytes.push(0);
+        assert!(UT::<u8>::from_bytes(&bytes).is_err());
+    }
+
 }
\ No newline at end of file
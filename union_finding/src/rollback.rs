@@ -0,0 +1,298 @@
+//!
+//! Weighted quick union that can undo its unions, as needed by offline algorithms
+//! such as dynamic connectivity with deletions or backtracking searches.
+//!
+//! Path compression rewrites the trees during every find and cannot be reverted,
+//! so this variant only balances by size: each union changes exactly one parent pointer
+//! and one size, which are logged and restored on rollback. Depth of any node is still at most logN.
+//!
+//! ```rust
+//!     # use union_finding::rollback::UT;
+//!     let mut ut = UT::new(4);
+//!     ut.union(0, 1);
+//!     let snapshot = ut.snapshot();
+//!     ut.union(1, 2);
+//!     assert!(ut.connected(0, 2));
+//!     ut.rollback(snapshot);
+//!     assert!(!ut.connected(0, 2));
+//!     assert!(ut.connected(0, 1));
+//! ```
+
+use crate::UnionFind;
+use crate::site;
+
+/// A point of the union history to roll back to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    len: usize,
+    // generation of the last logged union, 0 when the history was empty
+    generation: u64
+}
+
+pub struct UT {
+    id: Vec<u32>,
+    weights: Vec<u32>,
+    count: usize,
+    // roots linked by each effective union, as (child, parent, generation)
+    history: Vec<(u32, u32, u64)>,
+    // generation of the next logged union. Never reused, so an entry undone and replaced by another one is told apart
+    generation: u64
+}
+
+impl UT{
+
+    /// Return a new instance with n sites, each one in its own component
+    pub fn new(n: usize) -> UT{
+        site::check_capacity::<u32>(n);
+
+        UT{
+            id: (0..n as u32).collect(),
+            weights: vec![1; n],
+            count: n,
+            history: Vec::new(),
+            generation: 1
+        }
+    }
+
+    /// Navigate the tree until it finds the root. No compression, so it does not need &mut self
+    pub fn root(&self, mut i: usize) -> usize{
+        while self.id[i] != i as u32{
+            i = self.id[i] as usize;
+        }
+        i
+    }
+
+    pub fn connected(&self, p: usize, q: usize) -> bool{
+        self.root(p) == self.root(q)
+    }
+
+    /// Merge the components of p and q. Only unions merging two different components are logged
+    pub fn union(&mut self, p: usize, q: usize){
+        let mut pi = self.root(p);
+        let mut qi = self.root(q);
+
+        if pi == qi {return}
+
+        // link the smaller tree below the larger one
+        if self.weights[pi] > self.weights[qi]{
+            std::mem::swap(&mut pi, &mut qi);
+        }
+        self.id[pi] = qi as u32;
+        self.weights[qi] += self.weights[pi];
+        self.count -= 1;
+        self.history.push((pi as u32, qi as u32, self.generation));
+        self.generation += 1;
+    }
+
+    /// Return the number of components
+    pub fn count(&self) -> usize{
+        self.count
+    }
+
+    /// Return the current point of the history
+    pub fn snapshot(&self) -> Snapshot{
+        Snapshot {
+            len: self.history.len(),
+            generation: self.last_generation(self.history.len())
+        }
+    }
+
+    /// Generation of the last of the first len unions, 0 if len is 0
+    fn last_generation(&self, len: usize) -> u64{
+        if len == 0 { 0 } else { self.history[len - 1].2 }
+    }
+
+    /// Undo every union made after the snapshot was taken.
+    /// Panics if the snapshot has already been rolled back, even if unions were made again since then
+    pub fn rollback(&mut self, snapshot: Snapshot){
+        assert!(
+            snapshot.len <= self.history.len() && self.last_generation(snapshot.len) == snapshot.generation,
+            "snapshot at union {} has already been rolled back", snapshot.len
+        );
+
+        while self.history.len() > snapshot.len{
+            self.undo();
+        }
+    }
+
+    /// Undo the last union that merged two components. Return false if there is nothing to undo
+    pub fn undo(&mut self) -> bool{
+        match self.history.pop(){
+            Some((child, parent, _)) => {
+                let (child, parent) = (child as usize, parent as usize);
+                self.id[child] = child as u32;
+                self.weights[parent] -= self.weights[child];
+                self.count += 1;
+                true
+            },
+            None => false
+        }
+    }
+
+}
+
+impl UnionFind for UT{
+
+    fn new(n: usize) -> UT{
+        UT::new(n)
+    }
+
+    fn union(&mut self, p: usize, q: usize){
+        UT::union(self, p, q)
+    }
+
+    fn find(&mut self, p: usize) -> usize{
+        self.root(p)
+    }
+
+    fn connected(&mut self, p: usize, q: usize) -> bool{
+        UT::connected(self, p, q)
+    }
+
+    fn count(&self) -> usize{
+        self.count
+    }
+
+    fn len(&self) -> usize{
+        self.id.len()
+    }
+
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    #[test]
+    pub fn simple_init(){
+        let ut = UT::new(3);
+        assert_eq!(ut.id, [0,1,2]);
+        assert_eq!(3, ut.count());
+    }
+
+    #[test]
+    pub fn undo(){
+        let mut ut = UT::new(4);
+        ut.union(0, 1);
+        ut.union(2, 3);
+        ut.union(1, 3);
+        assert_eq!(1, ut.count());
+
+        assert!(ut.undo());
+        assert!(!ut.connected(0, 3));
+        assert!(ut.connected(2, 3));
+        assert_eq!(2, ut.count());
+
+        assert!(ut.undo());
+        assert!(ut.undo());
+        assert!(!ut.undo());
+        assert_eq!(ut.id, [0,1,2,3]);
+        assert_eq!(ut.weights, [1,1,1,1]);
+    }
+
+    #[test]
+    pub fn redundant_unions_are_not_logged(){
+        let mut ut = UT::new(3);
+        ut.union(0, 1);
+        ut.union(1, 0);
+
+        assert!(ut.undo());
+        assert!(!ut.connected(0, 1));
+        assert!(!ut.undo());
+    }
+
+    #[test]
+    pub fn interleaved_rollbacks(){
+        let mut ut = UT::new(6);
+        ut.union(0, 1);
+        let first = ut.snapshot();
+
+        ut.union(2, 3);
+        ut.union(1, 2);
+        let second = ut.snapshot();
+        ut.union(4, 5);
+        ut.union(5, 0);
+        assert_eq!(1, ut.count());
+
+        ut.rollback(second);
+        assert!(ut.connected(0, 3));
+        assert!(!ut.connected(0, 4));
+        assert!(!ut.connected(4, 5));
+
+        ut.union(3, 4);
+        assert!(ut.connected(0, 4));
+        assert_eq!(2, ut.count());
+
+        ut.rollback(first);
+        assert!(ut.connected(0, 1));
+        assert!(!ut.connected(1, 2));
+        assert!(!ut.connected(3, 4));
+        assert_eq!(5, ut.count());
+
+        // rolling back to the current point is a no-op
+        ut.rollback(first);
+        assert_eq!(5, ut.count());
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn stale_snapshot(){
+        let mut ut = UT::new(3);
+        ut.union(0, 1);
+        let snapshot = ut.snapshot();
+        ut.undo();
+        ut.rollback(snapshot);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn stale_snapshot_after_new_unions(){
+        // the history is as long as when the snapshot was taken, but it holds another union
+        let mut ut = UT::new(4);
+        ut.union(0, 1);
+        let snapshot = ut.snapshot();
+        ut.union(2, 3);
+        ut.undo();
+        ut.undo();
+        ut.union(1, 2);
+        ut.rollback(snapshot);
+    }
+
+    #[test]
+    pub fn snapshot_of_empty_history(){
+        let mut ut = UT::new(3);
+        let start = ut.snapshot();
+        ut.union(0, 1);
+        ut.undo();
+        ut.union(1, 2);
+
+        // every state goes back to the initial one
+        ut.rollback(start);
+        assert_eq!(3, ut.count());
+    }
+
+    #[test]
+    pub fn logarithmic_height(){
+        let n = 1 << 10;
+        let mut ut = UT::new(n);
+        let mut step = 1;
+        while step < n{
+            for i in (0..n).step_by(2 * step){
+                ut.union(i, i + step);
+            }
+            step *= 2;
+        }
+
+        let height = (0..n).map(|mut i| {
+            let mut depth = 0;
+            while ut.id[i] != i as u32 {
+                i = ut.id[i] as usize;
+                depth += 1;
+            }
+            depth
+        }).max().unwrap();
+        assert!(height <= 10);
+    }
+
+}
@@ -0,0 +1,97 @@
+//!
+//! Storage type of the sites and errors for invalid ones.
+//!
+//! The union-find arrays hold site indices, so their element type bounds how many sites fit:
+//! `u16` halves the memory of the default `u32` on small inputs, `u64` or `usize` allow more than 4 billion sites.
+//!
+//! ```rust
+//!     # use union_finding::weighting::UT;
+//!     # use union_finding::UnionFind;
+//!     let mut ut = UT::<u16>::with_sites(1000);
+//!     ut.union(1, 999);
+//!     assert!(ut.connected(1, 999));
+//!     assert!(ut.try_union(1, 1000).is_err());
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+/// Unsigned integer used to store site indices and tree sizes
+pub trait SiteIndex: Copy + Eq + Ord + fmt::Debug {
+    /// Largest number of sites that can be stored
+    const MAX: usize;
+
+    /// Panics if i does not fit
+    fn from_usize(i: usize) -> Self;
+
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_site_index {
+    ($($t:ty),*) => {
+        $(
+            impl SiteIndex for $t {
+                const MAX: usize = if (<$t>::MAX as u128) < (usize::MAX as u128) { <$t>::MAX as usize } else { usize::MAX };
+
+                fn from_usize(i: usize) -> Self {
+                    assert!(i <= <Self as SiteIndex>::MAX, "{} does not fit in {}", i, stringify!($t));
+                    i as $t
+                }
+
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_site_index!(u8, u16, u32, u64, usize);
+
+/// Returned by the fallible operations when a site is out of range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidSite {
+    pub site: usize,
+    /// Number of sites of the instance
+    pub len: usize
+}
+
+impl fmt::Display for InvalidSite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "site {} is out of range, there are {} sites", self.site, self.len)
+    }
+}
+
+impl Error for InvalidSite {}
+
+/// Panics if n sites do not fit in I
+pub(crate) fn check_capacity<I: SiteIndex>(n: usize) {
+    assert!(n <= I::MAX, "{} sites do not fit in {}", n, std::any::type_name::<I>());
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    #[test]
+    pub fn bounds(){
+        assert_eq!(255, <u8 as SiteIndex>::MAX);
+        assert_eq!(65535, <u16 as SiteIndex>::MAX);
+        assert_eq!(usize::MAX, <usize as SiteIndex>::MAX);
+        assert_eq!(7, u16::from_usize(7).to_usize());
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn overflow(){
+        u8::from_usize(256);
+    }
+
+    #[test]
+    pub fn display(){
+        let err = InvalidSite { site: 10, len: 3 };
+        assert_eq!("site 10 is out of range, there are 3 sites", err.to_string());
+    }
+
+}
@@ -0,0 +1,161 @@
+//!
+//! Successor with delete: a set over 0..n supporting remove(x) and successor(x),
+//! the smallest y >= x still in the set, in amortized near-constant time.
+//!
+//! Removing x unites it with x + 1, so every removed element is in the same component as the
+//! first element still present on its right, which is the largest member of the component.
+//! A sentinel site n collects the removed elements with nothing on their right.
+//! Predecessor is symmetric, with a second union-find where removing x unites it with x - 1.
+//!
+//! ```rust
+//!     # use union_finding::successor::SuccessorWithDelete;
+//!     let mut set = SuccessorWithDelete::new(10);
+//!     set.remove(2);
+//!     set.remove(3);
+//!     assert_eq!(Some(4), set.successor(2));
+//!     assert_eq!(Some(1), set.predecessor(3));
+//! ```
+
+use crate::weighting::UT;
+
+pub struct SuccessorWithDelete {
+    present: Vec<bool>,
+    len: usize,
+    // x is site x, the sentinel is n
+    next: UT,
+    // x is site x + 1, the sentinel is 0
+    prev: UT
+}
+
+impl SuccessorWithDelete {
+
+    /// Return the set of all the elements in 0..n
+    pub fn new(n: usize) -> SuccessorWithDelete {
+        SuccessorWithDelete {
+            present: vec![true; n],
+            len: n,
+            next: UT::new(n + 1),
+            prev: UT::new(n + 1)
+        }
+    }
+
+    /// Return the n of 0..n
+    pub fn universe(&self) -> usize {
+        self.present.len()
+    }
+
+    /// Return the number of elements still in the set
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check if x is still in the set. Panics if x is not in 0..n
+    pub fn contains(&self, x: usize) -> bool {
+        self.present[x]
+    }
+
+    /// Remove x from the set, nothing happens if it was already removed. Panics if x is not in 0..n
+    pub fn remove(&mut self, x: usize) {
+        if !self.present[x] {return}
+
+        self.present[x] = false;
+        self.len -= 1;
+        self.next.union(x, x + 1);
+        self.prev.union(x + 1, x);
+    }
+
+    /// Return the smallest element y >= x still in the set. Panics if x is not in 0..n
+    pub fn successor(&mut self, x: usize) -> Option<usize> {
+        assert!(x < self.present.len(), "{} is out of range 0..{}", x, self.present.len());
+
+        let y = self.next.find_max(x);
+        if y < self.present.len() { Some(y) } else { None }
+    }
+
+    /// Return the largest element y <= x still in the set. Panics if x is not in 0..n
+    pub fn predecessor(&mut self, x: usize) -> Option<usize> {
+        assert!(x < self.present.len(), "{} is out of range 0..{}", x, self.present.len());
+
+        let y = self.prev.find_min(x + 1);
+        if y > 0 { Some(y - 1) } else { None }
+    }
+
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+    use std::collections::BTreeSet;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    #[test]
+    pub fn full_set(){
+        let mut set = SuccessorWithDelete::new(5);
+        assert_eq!(5, set.len());
+        assert_eq!(Some(3), set.successor(3));
+        assert_eq!(Some(3), set.predecessor(3));
+    }
+
+    #[test]
+    pub fn remove(){
+        let mut set = SuccessorWithDelete::new(6);
+        set.remove(0);
+        set.remove(4);
+        set.remove(5);
+        set.remove(5);
+
+        assert_eq!(3, set.len());
+        assert!(!set.contains(4));
+        assert_eq!(Some(1), set.successor(0));
+        assert_eq!(None, set.successor(4));
+        assert_eq!(None, set.predecessor(0));
+        assert_eq!(Some(3), set.predecessor(5));
+    }
+
+    #[test]
+    pub fn empty(){
+        let mut set = SuccessorWithDelete::new(3);
+        for x in 0..3{
+            set.remove(x);
+        }
+        assert!(set.is_empty());
+        assert_eq!(None, set.successor(0));
+        assert_eq!(None, set.predecessor(2));
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn out_of_range(){
+        let mut set = SuccessorWithDelete::new(3);
+        set.successor(3);
+    }
+
+    #[test]
+    pub fn against_btreeset(){
+        let n = 300;
+        let mut rng = StdRng::seed_from_u64(16);
+        let mut set = SuccessorWithDelete::new(n);
+        let mut model: BTreeSet<usize> = (0..n).collect();
+
+        for _ in 0..2000{
+            let x = rng.gen_range(0..n);
+            match rng.gen_range(0..3){
+                0 => {
+                    set.remove(x);
+                    model.remove(&x);
+                },
+                1 => assert_eq!(model.range(x..).next().cloned(), set.successor(x)),
+                _ => assert_eq!(model.range(..=x).next_back().cloned(), set.predecessor(x))
+            }
+            assert_eq!(model.len(), set.len());
+            assert_eq!(model.contains(&x), set.contains(x));
+        }
+    }
+
+}
@@ -0,0 +1,230 @@
+//!
+//! Social network connectivity: given a log of `timestamp p q` entries, each one saying that
+//! members p and q became friends, find the earliest time at which all the members are connected
+//! and when each member joined the giant component.
+//!
+//! The giant component is the largest component with at least two members. When a union makes
+//! another component larger than the current giant, that one becomes the giant; on ties the current
+//! giant keeps the title. A member joins the giant the first time its component is the giant.
+//!
+//! ```rust
+//!     # use union_finding::timeline::analyze;
+//!     let log = vec![(10, 0, 1), (20, 2, 3), (30, 1, 2), (40, 0, 3)];
+//!     let timeline = analyze(4, log).unwrap();
+//!     assert_eq!(Some(30), timeline.fully_connected);
+//!     assert_eq!(vec![Some(10), Some(10), Some(30), Some(30)], timeline.joined_giant);
+//! ```
+//!
+//! Logs are expected in chronological order. `read_log` parses them from a text file, one entry per line.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+use crate::site::InvalidSite;
+use crate::weighting::UT;
+use crate::UnionFind;
+
+/// Connectivity timeline of a log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline<T> {
+    /// Timestamp of the entry connecting all the members, None if they never get connected
+    pub fully_connected: Option<T>,
+    /// Timestamp at which each member joined the giant component, None if it never did
+    pub joined_giant: Vec<Option<T>>
+}
+
+/// Replay the log on n members
+pub fn analyze<T, L>(n: usize, log: L) -> Result<Timeline<T>, InvalidSite>
+where
+    T: Clone,
+    L: IntoIterator<Item = (T, usize, usize)>
+{
+    let mut uf = UT::new(n);
+    // circular list of the members of each component, to visit them without scanning every site.
+    // Swapping the successors of two members of different lists merges the lists
+    let mut next: Vec<usize> = (0..n).collect();
+    let mut joined: Vec<Option<T>> = vec![None; n];
+    let mut giant: Option<usize> = None;
+    let mut giant_size = 1;
+    let mut fully_connected = None;
+
+    for (time, p, q) in log {
+        if uf.try_connected(p, q)? {continue}
+
+        let (pi, qi) = (uf.root(p), uf.root(q));
+        let size = uf.size(p) + uf.size(q);
+
+        if giant == Some(pi) || giant == Some(qi) {
+            // the other component joins the giant
+            let newcomer = if giant == Some(pi) { q } else { p };
+            mark(&next, newcomer, &mut joined, &time);
+        } else if size > giant_size {
+            // a new giant, made of two components that were not the giant
+            mark(&next, p, &mut joined, &time);
+            mark(&next, q, &mut joined, &time);
+        }
+
+        uf.union(p, q);
+        next.swap(p, q);
+
+        let root = uf.root(p);
+        if giant == Some(pi) || giant == Some(qi) || size > giant_size {
+            giant = Some(root);
+            giant_size = size;
+        }
+
+        if uf.count() == 1 {
+            fully_connected = Some(time);
+            break;
+        }
+    }
+
+    Ok(Timeline {
+        fully_connected,
+        joined_giant: joined
+    })
+}
+
+/// Set the time of the members in the list of start that have none yet
+fn mark<T: Clone>(next: &[usize], start: usize, joined: &mut [Option<T>], time: &T) {
+    let mut i = start;
+    loop {
+        if joined[i].is_none() {
+            joined[i] = Some(time.clone());
+        }
+        i = next[i];
+        if i == start {break}
+    }
+}
+
+/// Returned when a log cannot be read
+#[derive(Debug)]
+pub enum LogError {
+    Io(io::Error),
+    /// The line is not `timestamp p q`
+    Malformed { line: usize },
+    /// The line refers to a member that does not exist
+    InvalidMember { line: usize, member: usize }
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LogError::Io(e) => write!(f, "cannot read the log: {}", e),
+            LogError::Malformed { line } => write!(f, "line {} is not `timestamp p q`", line),
+            LogError::InvalidMember { line, member } => write!(f, "line {} refers to member {} which does not exist", line, member)
+        }
+    }
+}
+
+impl Error for LogError {}
+
+impl From<io::Error> for LogError {
+    fn from(e: io::Error) -> Self {
+        LogError::Io(e)
+    }
+}
+
+/// Read a log of n members, one `timestamp p q` entry per line. Empty lines are skipped
+pub fn read_log<T: FromStr + Clone, R: BufRead>(n: usize, reader: R) -> Result<Timeline<T>, LogError> {
+    let mut entries = Vec::new();
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = number + 1;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() {continue}
+
+        let entry = match fields[..] {
+            [time, p, q] => (time.parse().ok(), p.parse().ok(), q.parse().ok()),
+            _ => (None, None, None)
+        };
+        match entry {
+            (Some(time), Some(p), Some(q)) => {
+                for &member in [p, q].iter() {
+                    if member >= n {
+                        return Err(LogError::InvalidMember { line: number, member });
+                    }
+                }
+                entries.push((time, p, q));
+            },
+            _ => return Err(LogError::Malformed { line: number })
+        }
+    }
+
+    // every member has been checked already
+    Ok(analyze(n, entries).expect("members are checked while reading"))
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    #[test]
+    pub fn never_connected(){
+        let timeline = analyze(4, vec![(1, 0, 1), (2, 1, 0)]).unwrap();
+
+        assert_eq!(None, timeline.fully_connected);
+        assert_eq!(vec![Some(1), Some(1), None, None], timeline.joined_giant);
+    }
+
+    #[test]
+    pub fn giant_grows(){
+        let log = vec![(1, 0, 1), (2, 1, 2), (3, 3, 4), (4, 4, 2), (5, 5, 0), (6, 3, 1)];
+        let timeline = analyze(6, log).unwrap();
+
+        assert_eq!(Some(5), timeline.fully_connected);
+        assert_eq!(vec![Some(1), Some(1), Some(2), Some(4), Some(4), Some(5)], timeline.joined_giant);
+    }
+
+    #[test]
+    pub fn giant_overtaken(){
+        // {0, 1} is the giant until {2, 3, 4} grows larger without touching it
+        let log = vec![(1, 0, 1), (2, 2, 3), (3, 3, 4), (4, 5, 6), (5, 6, 0)];
+        let timeline = analyze(7, log).unwrap();
+
+        assert_eq!(None, timeline.fully_connected);
+        assert_eq!(vec![Some(1), Some(1), Some(3), Some(3), Some(3), Some(5), Some(5)], timeline.joined_giant);
+    }
+
+    #[test]
+    pub fn ties_keep_the_giant(){
+        let log = vec![(1, 0, 1), (2, 2, 3), (3, 3, 0)];
+        let timeline = analyze(4, log).unwrap();
+        assert_eq!(vec![Some(1), Some(1), Some(3), Some(3)], timeline.joined_giant);
+    }
+
+    #[test]
+    pub fn invalid_member(){
+        assert_eq!(Err(InvalidSite { site: 9, len: 3 }), analyze(3, vec![(1, 0, 9)]));
+    }
+
+    #[test]
+    pub fn read(){
+        let log = "2021-01-01T10:00 0 1\n\n2021-01-01T11:00 1 2\n2021-01-02T09:30 3 0\n";
+        let timeline: Timeline<String> = read_log(4, log.as_bytes()).unwrap();
+
+        assert_eq!(Some("2021-01-02T09:30".to_string()), timeline.fully_connected);
+        assert_eq!(Some("2021-01-01T11:00".to_string()), timeline.joined_giant[2]);
+    }
+
+    #[test]
+    pub fn read_errors(){
+        match read_log::<u64, _>(4, "1 0 1\n2 0\n".as_bytes()) {
+            Err(LogError::Malformed { line }) => assert_eq!(2, line),
+            other => panic!("unexpected {:?}", other)
+        }
+        match read_log::<u64, _>(4, "x 0 1\n".as_bytes()) {
+            Err(LogError::Malformed { line }) => assert_eq!(1, line),
+            other => panic!("unexpected {:?}", other)
+        }
+        match read_log::<u64, _>(4, "1 0 1\n2 0 4\n".as_bytes()) {
+            Err(e) => assert_eq!("line 2 refers to member 4 which does not exist", e.to_string()),
+            other => panic!("unexpected {:?}", other)
+        }
+    }
+
+}
@@ -0,0 +1,402 @@
+//!
+//! Hindley-Milner style unification of type terms. Type variables are the sites of a weighted quick union:
+//! unifying two variables is a `union`, and each component carries the constructor term it is bound to, if any,
+//! at its root, so resolving a variable is a `find`.
+//!
+//! ```rust
+//!     # use union_finding::unify::{Type, Unifier};
+//!     let mut u = Unifier::new();
+//!     let (a, b) = (u.fresh(), u.fresh());
+//!     let int = Type::constant("Int");
+//!
+//!     // a -> b  ~  Int -> a
+//!     u.unify(&Type::function(a.clone(), b.clone()), &Type::function(int.clone(), a)).unwrap();
+//!     assert_eq!(int, u.resolve(&b));
+//! ```
+//!
+//! A variable cannot be bound to a term containing itself, which would be an infinite type:
+//!
+//! ```rust
+//!     # use union_finding::unify::{Type, Unifier, UnifyError};
+//!     let mut u = Unifier::new();
+//!     let a = u.fresh();
+//!     let list = Type::con("List", vec![a.clone()]);
+//!     assert!(matches!(u.unify(&a, &list), Err(UnifyError::Occurs { .. })));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use crate::weighting::UT;
+
+/// Name of the constructor of function types
+const ARROW: &str = "->";
+
+/// A type term: a variable, or a constructor applied to arguments
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    Var(usize),
+    Con(String, Vec<Type>)
+}
+
+impl Type {
+
+    pub fn con<S: Into<String>>(name: S, args: Vec<Type>) -> Type {
+        Type::Con(name.into(), args)
+    }
+
+    /// A constructor without arguments, such as Int
+    pub fn constant<S: Into<String>>(name: S) -> Type {
+        Type::Con(name.into(), Vec::new())
+    }
+
+    /// The type of the functions from `from` to `to`
+    pub fn function(from: Type, to: Type) -> Type {
+        Type::Con(ARROW.to_string(), vec![from, to])
+    }
+
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Var(v) => write!(f, "t{}", v),
+            Type::Con(name, args) if name == ARROW && args.len() == 2 => write!(f, "({} -> {})", args[0], args[1]),
+            Type::Con(name, args) if args.is_empty() => write!(f, "{}", name),
+            Type::Con(name, args) => {
+                write!(f, "{}<", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {write!(f, ", ")?}
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ">")
+            }
+        }
+    }
+}
+
+/// Returned by `unify` when the two terms have no common instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnifyError {
+    /// Different constructors, or the same constructor with a different number of arguments
+    Mismatch { left: Type, right: Type },
+    /// The variable would be bound to a term containing itself
+    Occurs { var: usize, term: Type }
+}
+
+impl fmt::Display for UnifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnifyError::Mismatch { left, right } => write!(f, "cannot unify {} with {}", left, right),
+            UnifyError::Occurs { var, term } => write!(f, "t{} occurs in {}", var, term)
+        }
+    }
+}
+
+impl Error for UnifyError {}
+
+/// Type variables and their bindings
+pub struct Unifier {
+    uf: UT,
+    // term bound to each component, only meaningful at the roots. Always a constructor
+    terms: Vec<Option<Type>>
+}
+
+impl Unifier {
+
+    /// Return an instance without variables
+    pub fn new() -> Unifier {
+        Unifier {
+            uf: UT::new(0),
+            terms: Vec::new()
+        }
+    }
+
+    /// Return a new variable, not bound to anything
+    pub fn fresh(&mut self) -> Type {
+        let v = self.uf.add_site();
+        self.terms.push(None);
+        Type::Var(v)
+    }
+
+    /// Return the number of variables
+    pub fn len(&self) -> usize {
+        self.uf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.uf.is_empty()
+    }
+
+    /// Make the two terms equal, binding their variables.
+    /// On error the bindings made before the failure are kept. Panics on a variable not made by `fresh`
+    pub fn unify(&mut self, a: &Type, b: &Type) -> Result<(), UnifyError> {
+        match (self.class(a), self.class(b)) {
+            (Some(x), Some(y)) if x == y => Ok(()),
+            (Some(x), Some(y)) => {
+                match (self.terms[x].clone(), self.terms[y].clone()) {
+                    (None, None) => {
+                        self.uf.union(x, y);
+                        Ok(())
+                    },
+                    (None, Some(term)) => self.bind(x, y, term),
+                    (Some(term), None) => self.bind(y, x, term),
+                    (Some(s), Some(t)) => {
+                        self.check_heads(&s, &t)?;
+                        // one class inside the term of the other would make both terms infinite
+                        for &(var, term) in [(x, &t), (y, &s)].iter() {
+                            if self.occurs(var, term) {
+                                return Err(UnifyError::Occurs { var, term: self.resolve(term) });
+                            }
+                        }
+                        // merge the classes first: unifying them again, or any term sharing them, stops here
+                        self.uf.union(x, y);
+                        let root = self.uf.root(x);
+                        self.terms[x] = None;
+                        self.terms[y] = None;
+                        self.terms[root] = Some(s.clone());
+                        self.unify_args(&s, &t)
+                    }
+                }
+            },
+            (Some(x), None) => self.unify_class(x, b, false),
+            (None, Some(y)) => self.unify_class(y, a, true),
+            (None, None) => {
+                self.check_heads(a, b)?;
+                self.unify_args(a, b)
+            }
+        }
+    }
+
+    /// Unify the class x with a constructor. The constructor comes first in the errors when flipped
+    fn unify_class(&mut self, x: usize, term: &Type, flipped: bool) -> Result<(), UnifyError> {
+        match self.terms[x].clone() {
+            Some(bound) => {
+                let (s, t) = if flipped { (term, &bound) } else { (&bound, term) };
+                self.check_heads(s, t)?;
+                self.unify_args(s, t)
+            },
+            None => {
+                if self.occurs(x, term) {
+                    return Err(UnifyError::Occurs { var: x, term: self.resolve(term) });
+                }
+                self.terms[x] = Some(term.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Merge the unbound class x into the class y bound to term
+    fn bind(&mut self, x: usize, y: usize, term: Type) -> Result<(), UnifyError> {
+        if self.occurs(x, &term) {
+            return Err(UnifyError::Occurs { var: x, term: self.resolve(&term) });
+        }
+        self.uf.union(x, y);
+        let root = self.uf.root(x);
+        self.terms[y] = None;
+        self.terms[root] = Some(term);
+        Ok(())
+    }
+
+    /// Fail unless the two constructors have the same name and number of arguments
+    fn check_heads(&mut self, s: &Type, t: &Type) -> Result<(), UnifyError> {
+        match (s, t) {
+            (Type::Con(f, fargs), Type::Con(g, gargs)) if f == g && fargs.len() == gargs.len() => Ok(()),
+            _ => Err(UnifyError::Mismatch { left: self.resolve(s), right: self.resolve(t) })
+        }
+    }
+
+    /// Unify the arguments of two constructors with the same head
+    fn unify_args(&mut self, s: &Type, t: &Type) -> Result<(), UnifyError> {
+        if let (Type::Con(_, sargs), Type::Con(_, targs)) = (s, t) {
+            for (x, y) in sargs.iter().zip(targs.iter()) {
+                self.unify(x, y)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the root of the class of a variable, None for a constructor
+    fn class(&mut self, t: &Type) -> Option<usize> {
+        match t {
+            Type::Var(v) => Some(self.uf.root(*v)),
+            Type::Con(..) => None
+        }
+    }
+
+    /// Replace every bound variable with its term, and every unbound one with the root of its component
+    pub fn resolve(&mut self, t: &Type) -> Type {
+        match self.shallow(t) {
+            Type::Con(name, args) => Type::Con(name, args.iter().map(|arg| self.resolve(arg)).collect()),
+            var => var
+        }
+    }
+
+    /// Return the term bound to the variable, or the root of its component when it is unbound
+    fn shallow(&mut self, t: &Type) -> Type {
+        match t {
+            Type::Var(v) => {
+                let root = self.uf.root(*v);
+                match &self.terms[root] {
+                    Some(term) => term.clone(),
+                    None => Type::Var(root)
+                }
+            },
+            con => con.clone()
+        }
+    }
+
+    /// Check if the class with root x appears in t, looking through the terms of the other classes
+    fn occurs(&mut self, x: usize, t: &Type) -> bool {
+        match t {
+            Type::Var(v) => {
+                let root = self.uf.root(*v);
+                root == x || match self.terms[root].clone() {
+                    Some(term) => self.occurs(x, &term),
+                    None => false
+                }
+            },
+            Type::Con(_, args) => args.iter().any(|arg| self.occurs(x, arg))
+        }
+    }
+
+}
+
+impl Default for Unifier {
+    fn default() -> Self {
+        Unifier::new()
+    }
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    fn int() -> Type {
+        Type::constant("Int")
+    }
+
+    fn list(t: Type) -> Type {
+        Type::con("List", vec![t])
+    }
+
+    #[test]
+    pub fn variables(){
+        let mut u = Unifier::new();
+        let vars: Vec<Type> = (0..4).map(|_| u.fresh()).collect();
+        assert_eq!(4, u.len());
+
+        u.unify(&vars[0], &vars[1]).unwrap();
+        u.unify(&vars[2], &vars[1]).unwrap();
+        assert_eq!(u.resolve(&vars[0]), u.resolve(&vars[2]));
+        assert_ne!(u.resolve(&vars[0]), u.resolve(&vars[3]));
+
+        // binding one binds the whole class
+        u.unify(&vars[1], &int()).unwrap();
+        for v in &vars[..3]{
+            assert_eq!(int(), u.resolve(v));
+        }
+        assert_eq!(vars[3], u.resolve(&vars[3]));
+    }
+
+    #[test]
+    pub fn nested(){
+        let mut u = Unifier::new();
+        let (a, b, c) = (u.fresh(), u.fresh(), u.fresh());
+
+        // List<a> -> b  ~  c -> List<Int>, then c ~ List<List<Int>>
+        let left = Type::function(list(a.clone()), b.clone());
+        let right = Type::function(c.clone(), list(int()));
+        u.unify(&left, &right).unwrap();
+        u.unify(&c, &list(b.clone())).unwrap();
+
+        assert_eq!(list(int()), u.resolve(&a));
+        assert_eq!(list(list(int())), u.resolve(&c));
+        assert_eq!(u.resolve(&left), u.resolve(&right));
+        assert_eq!("(List<List<Int>> -> List<Int>)", u.resolve(&left).to_string());
+    }
+
+    #[test]
+    pub fn bound_variables(){
+        // two variables bound to unifiable terms are unified through their terms
+        let mut u = Unifier::new();
+        let (a, b, x) = (u.fresh(), u.fresh(), u.fresh());
+        u.unify(&a, &list(x.clone())).unwrap();
+        u.unify(&b, &list(int())).unwrap();
+        u.unify(&a, &b).unwrap();
+        assert_eq!(int(), u.resolve(&x));
+    }
+
+    #[test]
+    pub fn bound_variables_share_a_class(){
+        let mut u = Unifier::new();
+        let (a, b, x, y) = (u.fresh(), u.fresh(), u.fresh(), u.fresh());
+        u.unify(&a, &list(x.clone())).unwrap();
+        u.unify(&b, &list(y.clone())).unwrap();
+        u.unify(&a, &b).unwrap();
+
+        // one class with one term, and the arguments unified as well
+        let (ra, rb) = (u.class(&a), u.class(&b));
+        assert_eq!(ra, rb);
+        assert_eq!(u.class(&x), u.class(&y));
+        assert_eq!(1, (0..u.len()).filter(|&v| u.terms[v].is_some()).count());
+
+        // an unbound variable joining a bound class takes its term
+        let c = u.fresh();
+        u.unify(&c, &a).unwrap();
+        assert_eq!(u.class(&c), ra);
+        assert_eq!(list(u.resolve(&x)), u.resolve(&c));
+    }
+
+    #[test]
+    pub fn occurs_through_classes(){
+        let mut u = Unifier::new();
+        let (a, b) = (u.fresh(), u.fresh());
+        u.unify(&a, &list(b.clone())).unwrap();
+        assert!(matches!(u.unify(&b, &a), Err(UnifyError::Occurs { var: 1, .. })));
+        assert!(matches!(u.unify(&a, &b), Err(UnifyError::Occurs { var: 1, .. })));
+
+        // two bound classes, each term inside the other once they are merged
+        let (c, d, e) = (u.fresh(), u.fresh(), u.fresh());
+        u.unify(&c, &list(d.clone())).unwrap();
+        u.unify(&d, &list(e.clone())).unwrap();
+        match u.unify(&c, &d) {
+            Err(UnifyError::Occurs { var: 3, term }) => assert_eq!("List<List<t4>>", term.to_string()),
+            other => panic!("unexpected {:?}", other)
+        }
+    }
+
+    #[test]
+    pub fn mismatch(){
+        let mut u = Unifier::new();
+        let a = u.fresh();
+        u.unify(&a, &int()).unwrap();
+
+        // the error points at the innermost terms that differ
+        let err = u.unify(&list(a.clone()), &list(Type::constant("Bool"))).unwrap_err();
+        assert_eq!(UnifyError::Mismatch { left: int(), right: Type::constant("Bool") }, err);
+        assert_eq!("cannot unify Int with Bool", err.to_string());
+
+        // same name, different arity
+        let pair = Type::con("Pair", vec![int(), int()]);
+        assert!(u.unify(&pair, &Type::con("Pair", vec![int()])).is_err());
+    }
+
+    #[test]
+    pub fn occurs_check(){
+        let mut u = Unifier::new();
+        let (a, b) = (u.fresh(), u.fresh());
+
+        // the cycle goes through another variable
+        u.unify(&b, &list(a.clone())).unwrap();
+        match u.unify(&a, &Type::function(int(), b.clone())) {
+            Err(UnifyError::Occurs { var: 0, term }) => assert_eq!("(Int -> List<t0>)", term.to_string()),
+            other => panic!("unexpected {:?}", other)
+        }
+
+        // a variable unifies with itself
+        assert_eq!(Ok(()), u.unify(&a, &a));
+    }
+
+}
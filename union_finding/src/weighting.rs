@@ -4,44 +4,268 @@
 //! Keep track of size of each tree (number of objects) using an additional data structure
 //! Balance by linking root of smaller tree to root of larger tree
 //! 
+//! Each root also keeps the largest and smallest member of its component,
+//! so `find_max`, `find_min` and `size` cost as much as a find.
+//!
+//! By default trees are balanced by size and paths are halved during find.
+//! Other strategies can be selected with the builder, e.g. to compare them on the same workload.
+//!
+//! ```rust
+//!     # use union_finding::weighting::{UT, UnionBy, Compression};
+//!     let mut ut = UT::builder()
+//!         .union_by(UnionBy::Rank)
+//!         .compression(Compression::Full)
+//!         .build(10);
+//!     ut.union(0, 1);
+//!     assert!(ut.connected(1, 0));
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::UnionFind;
+use crate::site::{self, SiteIndex};
+use crate::encoding::{self, DecodeError, Reader};
+use crate::counter::Counter;
+#[cfg(feature = "instrument")]
+use crate::instrument::{self, AccessStats, Instrumented};
+
+/// How union chooses which root is linked below the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnionBy {
+    /// The root of the smaller tree goes below the root of the larger one
+    #[default]
+    Size,
+    /// The root with smaller rank (an upper bound of the height) goes below the other
+    Rank
+}
+
+/// How find shortens the path it walks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Leave the trees untouched
+    None,
+    /// Every other node in path points to its grandparent
+    #[default]
+    Halving,
+    /// Every node in path points to its grandparent
+    Splitting,
+    /// A second loop makes every node in path point to the root
+    Full
+}
+
+/// Parents and sizes are stored as I, u32 by default
+pub struct UT<I = u32> {
+    id: Vec<I>,
+    weights: Vec<I>,
+    // only used when linking by rank. Rank is at most logN, so it always fits in a byte
+    ranks: Vec<u8>,
+    // largest and smallest member of the component, meaningful for roots only
+    max: Vec<I>,
+    min: Vec<I>,
+    union_by: UnionBy,
+    compression: Compression,
+    count: usize,
+    counter: Counter
+}
+
+/// Build a UT with the chosen strategies and index type
+#[derive(Debug, Clone, Copy)]
+pub struct Builder<I = u32> {
+    union_by: UnionBy,
+    compression: Compression,
+    index: PhantomData<I>
+}
+
+impl<I: SiteIndex> Builder<I>{
+
+    pub fn union_by(mut self, union_by: UnionBy) -> Builder<I>{
+        self.union_by = union_by;
+        self
+    }
+
+    pub fn compression(mut self, compression: Compression) -> Builder<I>{
+        self.compression = compression;
+        self
+    }
+
+    /// Store parents and sizes as J instead
+    pub fn index<J: SiteIndex>(self) -> Builder<J>{
+        Builder{
+            union_by: self.union_by,
+            compression: self.compression,
+            index: PhantomData
+        }
+    }
+
+    /// Return a new instance with n sites, each one in its own component
+    pub fn build(self, n: usize) -> UT<I>{
+        let mut ut = UT::with_sites(n);
+        ut.union_by = self.union_by;
+        ut.compression = self.compression;
+        if self.union_by == UnionBy::Rank {
+            ut.ranks = vec![0; n];
+        }
+        ut
+    }
 
-pub struct UT {
-    id: Vec<u32>,
-    weights: Vec<u32>
+}
+
+impl<I: SiteIndex> Default for Builder<I>{
+    fn default() -> Self{
+        Builder{
+            union_by: UnionBy::default(),
+            compression: Compression::default(),
+            index: PhantomData
+        }
+    }
 }
 
 impl UT{
-    pub fn new(n: u32) -> UT{
+    pub fn new(n: usize) -> UT{
+        UT::with_sites(n)
+    }
+
+    /// Return a builder to choose the union and compression strategies
+    pub fn builder() -> Builder{
+        Builder::default()
+    }
+
+    /// Return an instance with no sites, with room for `capacity` sites before reallocating
+    pub fn with_capacity(capacity: usize) -> UT{
+        let mut ut = UT::with_sites(0);
+        ut.reserve(capacity);
+        ut
+    }
+}
+
+impl<I: SiteIndex> UT<I>{
+
+    /// Return a new instance with capacity n storing parents and sizes as I.
+    /// Panics if n sites do not fit in I
+    pub fn with_sites(n: usize) -> UT<I>{
+        site::check_capacity::<I>(n);
 
-        let mut r: Vec<u32> = Vec::with_capacity(n as usize);
-        let mut w: Vec<u32> = Vec::with_capacity(n as usize);
+        let mut r: Vec<I> = Vec::with_capacity(n);
+        let mut w: Vec<I> = Vec::with_capacity(n);
         for i in 0..n{
-            r.push(i);
-            w.push(1)
+            r.push(I::from_usize(i));
+            w.push(I::from_usize(1))
         }
 
         UT{
+            max: r.clone(),
+            min: r.clone(),
             id: r,
-            weights: w
+            weights: w,
+            ranks: Vec::new(),
+            union_by: UnionBy::default(),
+            compression: Compression::default(),
+            count: n,
+            counter: Counter::default()
         }
 
     }
 
+    /// Reserve room for at least `additional` more sites
+    pub fn reserve(&mut self, additional: usize){
+        self.id.reserve(additional);
+        self.weights.reserve(additional);
+        self.max.reserve(additional);
+        self.min.reserve(additional);
+        if self.union_by == UnionBy::Rank {
+            self.ranks.reserve(additional);
+        }
+    }
+
+    /// Append a new site in its own component and return its index.
+    /// Existing components are not touched. Panics if the new site does not fit in I
+    pub fn add_site(&mut self) -> usize{
+        let i = self.id.len();
+        site::check_capacity::<I>(i + 1);
+        self.id.push(I::from_usize(i));
+        self.weights.push(I::from_usize(1));
+        self.max.push(I::from_usize(i));
+        self.min.push(I::from_usize(i));
+        if self.union_by == UnionBy::Rank {
+            self.ranks.push(0);
+        }
+        self.count += 1;
+        i
+    }
+
+    pub fn union_by(&self) -> UnionBy{
+        self.union_by
+    }
+
+    pub fn compression(&self) -> Compression{
+        self.compression
+    }
+
+    /// Return the number of sites
+    pub fn len(&self) -> usize{
+        self.id.len()
+    }
+
+    /// Check if there are no sites
+    pub fn is_empty(&self) -> bool{
+        self.id.is_empty()
+    }
+
     pub fn root(&mut self, mut i: usize)->usize{
 
-        while self.id[i as usize] != i as u32{
-            /*
-                A solution to make this op faster is path compression.
-                With this one-line trick we make every other node in path
-                points to its grandparent (halving path length).
-                
-                Alternatively, we might add a second loop to point each visited node points to its root.
-            */
-            self.id[i] = self.id[self.id[i] as usize];
-            i = self.id[i] as usize;
+        /*
+            A solution to make this op faster is path compression.
+            With a one-line trick (halving) we make every other node in path
+            points to its grandparent (halving path length).
+
+            Alternatively, we might add a second loop to point each visited node points to its root.
+        */
+        match self.compression {
+            Compression::None => {
+                while self.id[i].to_usize() != i{
+                    i = self.id[i].to_usize();
+                    self.counter.read(2);
+                }
+            },
+            Compression::Halving => {
+                while self.id[i].to_usize() != i{
+                    self.id[i] = self.id[self.id[i].to_usize()];
+                    i = self.id[i].to_usize();
+                    self.counter.read(4);
+                    self.counter.write(1);
+                }
+            },
+            Compression::Splitting => {
+                while self.id[i].to_usize() != i{
+                    let parent = self.id[i].to_usize();
+                    self.id[i] = self.id[parent];
+                    i = parent;
+                    self.counter.read(3);
+                    self.counter.write(1);
+                }
+            },
+            Compression::Full => {
+                let mut root = i;
+                while self.id[root].to_usize() != root{
+                    root = self.id[root].to_usize();
+                    self.counter.read(2);
+                }
+                self.counter.read(1);
+                while i != root{
+                    let parent = self.id[i].to_usize();
+                    self.id[i] = I::from_usize(root);
+                    i = parent;
+                    self.counter.read(1);
+                    self.counter.write(1);
+                }
+            }
+        }
+        // the read finding i points to itself
+        if self.compression != Compression::Full{
+            self.counter.read(1);
         }
 
-        self.id[i] as usize
+        i
     }
 
 
@@ -57,14 +281,219 @@ impl UT{
 
         if pi == qi {return}
         
-        if self.weights[pi] > self.weights[qi]{
-            self.id[qi] = pi as u32;
-            self.weights[pi] += self.weights[qi];
+        // sizes are kept with both strategies, they are needed to enumerate the components
+        let size = I::from_usize(self.weights[pi].to_usize() + self.weights[qi].to_usize());
+        let max = self.max[pi].max(self.max[qi]);
+        let min = self.min[pi].min(self.min[qi]);
+        self.counter.read(6);
+        let p_on_top = match self.union_by {
+            UnionBy::Size => {
+                self.counter.read(2);
+                self.weights[pi] > self.weights[qi]
+            },
+            UnionBy::Rank => {
+                self.counter.read(4);
+                if self.ranks[pi] == self.ranks[qi] {
+                    self.ranks[qi] += 1;
+                    self.counter.write(1);
+                }
+                self.ranks[pi] > self.ranks[qi]
+            }
+        };
+
+        let root = if p_on_top{
+            self.id[qi] = I::from_usize(pi);
+            pi
         }else{
-            self.id[pi] = qi as u32;
-            self.weights[qi] += self.weights[pi];    
+            self.id[pi] = I::from_usize(qi);
+            qi
+        };
+        self.weights[root] = size;
+        self.max[root] = max;
+        self.min[root] = min;
+        self.counter.write(4);
+        self.count -= 1;
+
+    }
+
+    /// Return the number of components
+    pub fn count(&self) -> usize{
+        self.count
+    }
+
+    /// Return the number of sites in the component containing p
+    pub fn size(&mut self, p: usize) -> usize{
+        let r = self.root(p);
+        self.counter.read(1);
+        self.weights[r].to_usize()
+    }
+
+    /// Return the largest site in the component containing p
+    pub fn find_max(&mut self, p: usize) -> usize{
+        let r = self.root(p);
+        self.counter.read(1);
+        self.max[r].to_usize()
+    }
+
+    /// Return the smallest site in the component containing p
+    pub fn find_min(&mut self, p: usize) -> usize{
+        let r = self.root(p);
+        self.counter.read(1);
+        self.min[r].to_usize()
+    }
+
+    /// Return every component as the sorted list of its members.
+    /// Components are ordered by their smallest member.
+    pub fn components(&mut self) -> Vec<Vec<usize>>{
+        // position of each root inside the result, so each site is visited once
+        let mut slot: Vec<Option<usize>> = vec![None; self.id.len()];
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.count);
+
+        for i in 0..self.id.len(){
+            let r = self.root(i);
+            let c = match slot[r]{
+                Some(c) => c,
+                None => {
+                    // the size of the tree tells how many members we are going to push
+                    components.push(Vec::with_capacity(self.weights[r].to_usize()));
+                    slot[r] = Some(components.len() - 1);
+                    components.len() - 1
+                }
+            };
+            components[c].push(i);
+        }
+
+        components
+    }
+
+    /// Return the sorted members of the component containing p
+    pub fn component_of(&mut self, p: usize) -> Vec<usize>{
+        let r = self.root(p);
+        let mut members = Vec::with_capacity(self.weights[r].to_usize());
+
+        for i in 0..self.id.len(){
+            if self.root(i) == r {
+                members.push(i);
+            }
         }
 
+        members
+    }
+
+    /// Encode the state, strategies included, see the encoding module
+    pub fn to_bytes(&self) -> Vec<u8>{
+        let strategies = [self.union_by as u8, self.compression as u8];
+        let mut bytes = encoding::encode(encoding::WEIGHTING, &strategies, &self.id);
+        bytes.extend_from_slice(&self.ranks);
+        bytes
+    }
+
+    /// Restore a state encoded by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<UT<I>, DecodeError>{
+        let (mut reader, width) = Reader::new(bytes, encoding::WEIGHTING)?;
+        let union_by = match reader.byte()?{
+            0 => UnionBy::Size,
+            1 => UnionBy::Rank,
+            _ => return Err(DecodeError::Header)
+        };
+        let compression = match reader.byte()?{
+            0 => Compression::None,
+            1 => Compression::Halving,
+            2 => Compression::Splitting,
+            3 => Compression::Full,
+            _ => return Err(DecodeError::Header)
+        };
+        let id: Vec<I> = reader.id(width)?;
+        let n = id.len();
+        let ranks = match union_by{
+            UnionBy::Rank => reader.take(n)?.to_vec(),
+            UnionBy::Size => Vec::new()
+        };
+        reader.finish()?;
+
+        // sizes and extremes are not stored, they are computed from the sites below each root
+        let roots = encoding::roots(&id)?;
+        let mut sizes = vec![0; n];
+        let mut max: Vec<I> = (0..n).map(I::from_usize).collect();
+        let mut min = max.clone();
+        for (site, &r) in roots.iter().enumerate(){
+            sizes[r] += 1;
+            max[r] = max[r].max(I::from_usize(site));
+            min[r] = min[r].min(I::from_usize(site));
+        }
+
+        // a parent always has a larger rank than its children, and a root of rank r has at least 2^r sites
+        for (site, &rank) in ranks.iter().enumerate(){
+            let parent = id[site].to_usize();
+            let valid = if parent == site{
+                rank < 64 && (1u64 << rank) <= sizes[site] as u64
+            }else{
+                rank < ranks[parent]
+            };
+            if !valid{
+                return Err(DecodeError::InvalidRank { site });
+            }
+        }
+
+        let count = roots.iter().enumerate().filter(|&(i, &r)| r == i).count();
+        let weights = sizes.into_iter().map(|s| I::from_usize(s.max(1))).collect();
+
+        Ok(UT{
+            id,
+            weights,
+            ranks,
+            max,
+            min,
+            union_by,
+            compression,
+            count,
+            counter: Counter::default()
+        })
+    }
+
+}
+
+impl<I: SiteIndex> UnionFind for UT<I>{
+
+    fn new(n: usize) -> UT<I>{
+        UT::with_sites(n)
+    }
+
+    fn union(&mut self, p: usize, q: usize){
+        UT::union(self, p, q)
+    }
+
+    fn find(&mut self, p: usize) -> usize{
+        self.root(p)
+    }
+
+    fn connected(&mut self, p: usize, q: usize) -> bool{
+        UT::connected(self, p, q)
+    }
+
+    fn count(&self) -> usize{
+        self.count
+    }
+
+    fn len(&self) -> usize{
+        self.id.len()
+    }
+
+}
+
+#[cfg(feature = "instrument")]
+impl<I: SiteIndex> Instrumented for UT<I>{
+
+    fn set_instrumented(&mut self, enabled: bool){
+        self.counter.set_enabled(enabled);
+    }
+
+    fn access_stats(&self) -> AccessStats{
+        self.counter.stats(instrument::max_height(&self.id, I::to_usize))
+    }
+
+    fn reset_access_stats(&mut self){
+        self.counter.reset();
     }
 
 }
@@ -73,6 +502,7 @@ impl UT{
 mod test{
 
     use super::*;
+    use crate::encoding::DecodeError;
 
     #[test]
     pub fn simple_init(){
@@ -86,20 +516,274 @@ mod test{
     pub fn connected_shoud_fail(){
         let mut ut = UT::new(10);
 
-        assert_eq!(false, ut.connected(0, 1));
-        assert_eq!(false, ut.connected(0, 2));
-        assert_eq!(false, ut.connected(3, 2));
+        assert!(!ut.connected(0, 1));
+        assert!(!ut.connected(0, 2));
+        assert!(!ut.connected(3, 2));
     }
 
+    #[test]
     pub fn connected(){
         let mut ut = UT::new(4);
         ut.union(0, 1);
         ut.union(2, 3);
         
-        assert_eq!(true, ut.connected(0, 1));
-        assert_eq!(true, ut.connected(2, 3));
-        assert_eq!(false, ut.connected(0, 3));
+        assert!(ut.connected(0, 1));
+        assert!(ut.connected(2, 3));
+        assert!(!ut.connected(0, 3));
     
     }
 
+    #[test]
+    pub fn components(){
+        let mut ut = UT::new(8);
+        ut.union(1, 4);
+        ut.union(4, 5);
+        ut.union(2, 3);
+        ut.union(6, 2);
+        ut.union(7, 3);
+
+        assert_eq!(3, ut.count());
+        assert_eq!(vec![vec![0], vec![1, 4, 5], vec![2, 3, 6, 7]], ut.components());
+    }
+
+    #[test]
+    pub fn component_of(){
+        let mut ut = UT::new(8);
+        ut.union(1, 4);
+        ut.union(4, 5);
+
+        assert_eq!(vec![1, 4, 5], ut.component_of(5));
+        assert_eq!(vec![0], ut.component_of(0));
+    }
+
+    #[test]
+    pub fn add_site(){
+        let mut ut = UT::with_capacity(2);
+        assert!(ut.is_empty());
+        assert_eq!(0, ut.add_site());
+        assert_eq!(1, ut.add_site());
+        ut.union(0, 1);
+
+        assert_eq!(2, ut.add_site());
+        assert_eq!(3, ut.len());
+        assert_eq!(2, ut.count());
+        assert!(ut.connected(0, 1));
+        assert!(!ut.connected(1, 2));
+
+        ut.union(2, 0);
+        assert_eq!(1, ut.count());
+    }
+
+    #[test]
+    pub fn growth_keeps_components(){
+        let mut ut = UT::new(4);
+        ut.union(0, 1);
+        ut.union(2, 3);
+        let before = ut.components();
+
+        for _ in 0..100{
+            ut.add_site();
+        }
+        assert_eq!(102, ut.count());
+        assert_eq!(before[..], ut.components()[..2]);
+
+        // a grown site still balances by size
+        ut.union(103, 0);
+        assert_eq!(vec![0, 1, 103], ut.component_of(103));
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn add_site_overflow(){
+        let mut ut = UT::<u8>::with_sites(255);
+        ut.add_site();
+    }
+
+    fn height<I: SiteIndex>(ut: &UT<I>) -> usize{
+        (0..ut.id.len()).map(|mut i| {
+            let mut depth = 0;
+            while ut.id[i].to_usize() != i {
+                i = ut.id[i].to_usize();
+                depth += 1;
+            }
+            depth
+        }).max().unwrap_or(0)
+    }
+
+    #[test]
+    pub fn builder_defaults(){
+        let ut = UT::builder().build(3);
+        assert_eq!(UnionBy::Size, ut.union_by());
+        assert_eq!(Compression::Halving, ut.compression());
+        assert_eq!(ut.id, [0,1,2]);
+
+        let ut = UT::builder().index::<u16>().union_by(UnionBy::Rank).build(3);
+        assert_eq!(UnionBy::Rank, ut.union_by());
+        assert_eq!(ut.ranks, [0,0,0]);
+    }
+
+    #[test]
+    pub fn strategies_agree(){
+        use rand::{Rng, SeedableRng};
+        use rand::rngs::StdRng;
+
+        let n = 500;
+        let mut rng = StdRng::seed_from_u64(7);
+        let pairs: Vec<(usize, usize)> = (0..400).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n))).collect();
+
+        let mut reference = UT::new(n);
+        for &(p, q) in &pairs{
+            reference.union(p, q);
+        }
+        let expected = reference.components();
+
+        let compressions = [Compression::None, Compression::Halving, Compression::Splitting, Compression::Full];
+        for &union_by in [UnionBy::Size, UnionBy::Rank].iter(){
+            for &compression in compressions.iter(){
+                let mut ut = UT::builder().union_by(union_by).compression(compression).build(n);
+                for &(p, q) in &pairs{
+                    ut.union(p, q);
+                }
+                // with no compression the height only depends on the balancing
+                if compression == Compression::None{
+                    assert!(height(&ut) <= 9);
+                }
+                assert_eq!(expected, ut.components(), "{:?} {:?}", union_by, compression);
+                assert_eq!(reference.count(), ut.count());
+            }
+        }
+    }
+
+    #[test]
+    pub fn full_compression(){
+        let mut ut = UT::builder().compression(Compression::Full).build(8);
+        // 0 <- 1 <- 2 <- 3 chains built by hand to check the flattening
+        ut.id = vec![0, 0, 1, 2, 4, 5, 6, 7];
+        ut.root(3);
+        assert_eq!(ut.id[..4], [0, 0, 0, 0]);
+
+        let mut ut = UT::builder().compression(Compression::Splitting).build(8);
+        ut.id = vec![0, 0, 1, 2, 3, 5, 6, 7];
+        ut.root(4);
+        assert_eq!(ut.id[..5], [0, 0, 0, 1, 2]);
+    }
+
+    #[test]
+    pub fn rank_growth(){
+        let mut ut = UT::builder().union_by(UnionBy::Rank).build(2);
+        ut.union(0, 1);
+        let r = ut.root(0);
+        assert_eq!(1, ut.ranks[r]);
+
+        let s = ut.add_site();
+        ut.union(s, 0);
+        assert_eq!(r, ut.root(s));
+        assert_eq!(1, ut.ranks[r]);
+    }
+
+    #[test]
+    pub fn round_trip(){
+        for &union_by in [UnionBy::Size, UnionBy::Rank].iter(){
+            let mut ut = UT::builder().union_by(union_by).compression(Compression::Full).build(8);
+            ut.union(0, 1);
+            ut.union(2, 3);
+            ut.union(1, 3);
+            ut.union(6, 7);
+
+            let mut restored: UT = UT::from_bytes(&ut.to_bytes()).unwrap();
+            assert_eq!(ut.id, restored.id);
+            assert_eq!(ut.ranks, restored.ranks);
+            assert_eq!(union_by, restored.union_by());
+            assert_eq!(Compression::Full, restored.compression());
+            assert_eq!(ut.count(), restored.count());
+            assert_eq!(ut.components(), restored.components());
+
+            // sizes are rebuilt, so the smaller tree still goes below
+            restored.union(6, 0);
+            let r = restored.root(0);
+            assert_eq!(r, restored.root(6));
+            assert_eq!(6, restored.weights[r]);
+        }
+    }
+
+    #[test]
+    pub fn corrupted_bytes(){
+        let mut ut = UT::builder().union_by(UnionBy::Rank).build(3);
+        ut.union(0, 1);
+        let bytes = ut.to_bytes();
+        let n = bytes.len();
+        assert_eq!(ut.ranks, [0, 1, 0]);
+
+        // a root of rank 1 with a single site
+        let mut rank = bytes.clone();
+        rank[n - 1] = 1;
+        assert_eq!(Err(DecodeError::InvalidRank { site: 2 }), UT::<u32>::from_bytes(&rank).map(|_| ()));
+
+        // 0 -> 1 -> 0. Parents are the 3 entries of 4 bytes before the ranks
+        let mut cycle = bytes.clone();
+        cycle[n - 3 - 8] = 0;
+        assert!(matches!(UT::<u32>::from_bytes(&cycle), Err(DecodeError::Cycle { .. })));
+
+        let mut strategy = bytes;
+        strategy[6] = 7;
+        assert_eq!(Err(DecodeError::Header), UT::<u32>::from_bytes(&strategy).map(|_| ()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn serde_round_trip(){
+        let mut ut = UT::<u16>::with_sites(5);
+        ut.union(3, 4);
+
+        let json = serde_json::to_string(&ut).unwrap();
+        let mut restored: UT<u16> = serde_json::from_str(&json).unwrap();
+        assert!(restored.connected(4, 3));
+        assert_eq!(4, restored.count());
+
+        let mut corrupted = ut.to_bytes();
+        corrupted[5] = 9;
+        let json = serde_json::to_string(&corrupted).unwrap();
+        assert!(serde_json::from_str::<UT<u16>>(&json).is_err());
+    }
+
+    #[test]
+    pub fn canonical_elements(){
+        let mut ut = UT::new(10);
+        assert_eq!(3, ut.find_max(3));
+        assert_eq!(3, ut.find_min(3));
+        assert_eq!(1, ut.size(3));
+
+        ut.union(1, 2);
+        ut.union(6, 9);
+        ut.union(2, 6);
+        ut.union(4, 0);
+
+        for &p in [1, 2, 6, 9].iter(){
+            assert_eq!(9, ut.find_max(p));
+            assert_eq!(1, ut.find_min(p));
+            assert_eq!(4, ut.size(p));
+        }
+        assert_eq!(4, ut.find_max(0));
+        assert_eq!(0, ut.find_min(4));
+        assert_eq!(2, ut.size(0));
+    }
+
+    #[test]
+    pub fn canonical_elements_with_every_strategy(){
+        let empty = UT::builder().union_by(UnionBy::Rank).compression(Compression::None).build(5);
+        let mut ut: UT = UT::from_bytes(&empty.to_bytes()).unwrap();
+        let added = ut.add_site();
+        ut.union(added, 2);
+        ut.union(3, 2);
+
+        assert_eq!(5, ut.find_max(3));
+        assert_eq!(2, ut.find_min(5));
+        assert_eq!(3, ut.size(2));
+
+        let mut restored: UT = UT::from_bytes(&ut.to_bytes()).unwrap();
+        assert_eq!(5, restored.find_max(2));
+        assert_eq!(2, restored.find_min(3));
+        assert_eq!(3, restored.size(5));
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,168 @@
+//!
+//! Weighted quick union where every component carries a value, such as a sum, a count or a bounding box.
+//! Each root holds the value of its component and `union` combines the two values with the merge
+//! function given at construction, so the aggregate of a component is always available without recomputing it.
+//!
+//! ```rust
+//!     # use union_finding::with_data::UnionFindWith;
+//!     // total weight of each component
+//!     let mut uf = UnionFindWith::new(vec![3, 1, 4, 1, 5], |a, b| a + b);
+//!     uf.union(0, 2);
+//!     uf.union(2, 4);
+//!     assert_eq!(12, *uf.data(4));
+//!     assert_eq!(1, *uf.data(1));
+//! ```
+
+use crate::weighting::UT;
+
+/// Union-find with a value of type D per component. The merge function defaults to a plain function pointer,
+/// so `UnionFindWith<D>` can be named in fields and signatures
+pub struct UnionFindWith<D, F = fn(D, D) -> D> {
+    uf: UT,
+    // value of each component, Some only at the roots
+    data: Vec<Option<D>>,
+    merge: F
+}
+
+impl<D, F: FnMut(D, D) -> D> UnionFindWith<D, F> {
+
+    /// Return an instance with a site for each value, each one in its own component.
+    /// `merge` receives the value of the component of p first, then the one of q
+    pub fn new(data: Vec<D>, merge: F) -> UnionFindWith<D, F> {
+        UnionFindWith {
+            uf: UT::new(data.len()),
+            data: data.into_iter().map(Some).collect(),
+            merge
+        }
+    }
+
+    /// Append a new site in its own component holding the value, and return its index
+    pub fn add_site(&mut self, value: D) -> usize {
+        let i = self.uf.add_site();
+        self.data.push(Some(value));
+        i
+    }
+
+    /// Return the number of sites
+    pub fn len(&self) -> usize {
+        self.uf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.uf.is_empty()
+    }
+
+    /// Return the number of components
+    pub fn count(&self) -> usize {
+        self.uf.count()
+    }
+
+    /// Return the root of the component containing p
+    pub fn find(&mut self, p: usize) -> usize {
+        self.uf.root(p)
+    }
+
+    pub fn connected(&mut self, p: usize, q: usize) -> bool {
+        self.uf.connected(p, q)
+    }
+
+    /// Return the number of sites in the component containing p
+    pub fn size(&mut self, p: usize) -> usize {
+        self.uf.size(p)
+    }
+
+    /// Connect p and q, merging the values of their components
+    pub fn union(&mut self, p: usize, q: usize) {
+        let pi = self.uf.root(p);
+        let qi = self.uf.root(q);
+        if pi == qi {return}
+
+        let dp = self.data[pi].take().expect("roots hold a value");
+        let dq = self.data[qi].take().expect("roots hold a value");
+        self.uf.union(pi, qi);
+
+        let root = self.uf.root(pi);
+        self.data[root] = Some((self.merge)(dp, dq));
+    }
+
+    /// Return the value of the component containing p
+    pub fn data(&mut self, p: usize) -> &D {
+        let root = self.uf.root(p);
+        self.data[root].as_ref().expect("roots hold a value")
+    }
+
+    /// Return the value of the component containing p, to update it in place
+    pub fn data_mut(&mut self, p: usize) -> &mut D {
+        let root = self.uf.root(p);
+        self.data[root].as_mut().expect("roots hold a value")
+    }
+
+}
+
+#[cfg(test)]
+mod test{
+
+    use super::*;
+
+    #[test]
+    pub fn sums_and_counts(){
+        let mut uf = UnionFindWith::new((0..8).map(|i| (i, 1)).collect(), |(s1, c1), (s2, c2)| (s1 + s2, c1 + c2));
+        uf.union(1, 3);
+        uf.union(5, 7);
+        uf.union(3, 7);
+        // already connected, nothing is merged twice
+        uf.union(1, 5);
+
+        assert_eq!((16, 4), *uf.data(7));
+        assert_eq!(4, uf.size(1));
+        assert_eq!((0, 1), *uf.data(0));
+        assert_eq!(5, uf.count());
+    }
+
+    #[test]
+    pub fn bounding_boxes(){
+        type Box = (i32, i32, i32, i32);
+        let points = [(0, 0), (5, 2), (-1, 3), (2, -4)];
+        let boxes: Vec<Box> = points.iter().map(|&(x, y)| (x, y, x, y)).collect();
+        let mut uf = UnionFindWith::new(boxes, |a: Box, b: Box| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)));
+
+        uf.union(0, 1);
+        uf.union(2, 1);
+        assert_eq!((-1, 0, 5, 3), *uf.data(0));
+        assert_eq!((2, -4, 2, -4), *uf.data(3));
+    }
+
+    #[test]
+    pub fn merge_order(){
+        // the value of p's component comes first, whichever root wins
+        let mut uf = UnionFindWith::new(vec!["a".to_string(), "b".to_string(), "c".to_string()], |a, b| a + &b);
+        uf.union(0, 1);
+        uf.union(2, 0);
+        assert_eq!("cab", uf.data(1));
+    }
+
+    #[test]
+    pub fn default_merge_type(){
+        fn longest(a: String, b: String) -> String { if b.len() > a.len() { b } else { a } }
+
+        let mut uf: UnionFindWith<String> = UnionFindWith::new(vec!["ab".to_string(), "abc".to_string()], longest);
+        uf.union(0, 1);
+        assert_eq!("abc", uf.data(0));
+    }
+
+    #[test]
+    pub fn grow_and_update(){
+        let mut uf = UnionFindWith::new(Vec::new(), |a: u32, b| a.max(b));
+        assert!(uf.is_empty());
+
+        let a = uf.add_site(3);
+        let b = uf.add_site(9);
+        uf.union(a, b);
+        *uf.data_mut(a) += 1;
+
+        assert_eq!(10, *uf.data(b));
+        assert_eq!(uf.find(a), uf.find(b));
+        assert_eq!(2, uf.len());
+    }
+
+}